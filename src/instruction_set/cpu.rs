@@ -0,0 +1,31 @@
+/// The processor whose instruction set the decoder accepts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Cpu {
+    /// The documented 8086 and 8088 instruction set
+    #[default]
+    Intel8086,
+    /// The 8086 and 8088 as the silicon executes them, also accepting the undocumented opcodes
+    /// and aliases such as `pop cs`, `salc` and the `60`-`6F` jumps
+    Intel8086Silicon,
+    /// The 80186 and 80188, adding PUSHA/POPA, BOUND, ENTER/LEAVE, PUSH and IMUL with immediate
+    /// data, INS/OUTS and shifts by an immediate count
+    Intel80186,
+    /// The 80286, adding ARPL and the `0F` two-byte opcodes for protected mode to the 80186
+    Intel80286,
+    /// The NEC V20 and V30, adding bit manipulation, bit field, packed decimal string and
+    /// nibble rotation instructions behind `0F` to the 80186 instruction set
+    NecV20,
+}
+
+impl Cpu {
+    /// Check whether `0F` starts a two-byte opcode instead of being `pop cs`
+    pub(crate) fn has_two_byte_opcodes(&self) -> bool {
+        matches!(self, Cpu::Intel80286 | Cpu::NecV20)
+    }
+
+    /// Check whether the undocumented 8086 opcodes and aliases are decoded
+    pub(crate) fn has_undocumented_opcodes(&self) -> bool {
+        matches!(self, Cpu::Intel8086Silicon)
+    }
+}
@@ -0,0 +1,177 @@
+use std::iter::FusedIterator;
+
+use super::{cpu::Cpu, encoded::EncodedInstruction, errors::DecodeError, instruction::Instruction};
+
+/// Decodes machine code of one processor into structured instructions
+///
+/// # Examples
+/// ```
+/// use inst_decoding_8086::{Decoder, Mnemonic, Operand, Register};
+///
+/// let decoder = Decoder::default();
+/// let instruction = decoder.decode(&[0x89, 0xD9], 0).unwrap();
+/// assert_eq!(instruction.mnemonic, Mnemonic::Mov);
+/// assert_eq!(
+///     instruction.operands,
+///     [Operand::Register(Register::CX), Operand::Register(Register::BX)]
+/// );
+/// assert_eq!(instruction.to_string(), "mov cx, bx");
+///
+/// // A `jnz` at address 2 that jumps back to address 0
+/// let instruction = decoder.decode(&[0x75, 0xFC], 2).unwrap();
+/// assert_eq!(instruction.operands, [Operand::RelativeTarget(-4)]);
+/// assert_eq!(instruction.next_address(), 4);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Decoder {
+    cpu: Cpu,
+}
+
+impl Decoder {
+    /// Creates a decoder accepting the instruction set of `cpu`
+    pub fn new(cpu: Cpu) -> Self {
+        Decoder { cpu }
+    }
+
+    /// Gets the processor whose instruction set the decoder accepts
+    pub fn cpu(&self) -> Cpu {
+        self.cpu
+    }
+
+    /// Decodes the instruction at the start of `bytes`, consuming only the bytes it is made of.
+    /// The `address` of its first byte is what relative jump targets are resolved against.
+    ///
+    /// # Errors
+    /// Fails when `bytes` ends before the instruction does, or when they do not encode an
+    /// instruction of the processor.
+    pub fn decode(&self, bytes: &[u8], address: u16) -> Result<Instruction, DecodeError> {
+        EncodedInstruction::decode(bytes, address, self.cpu)?.decoded()
+    }
+
+    /// Decodes the instructions in `bytes` one after another as the iterator is advanced. The
+    /// first byte sits at address `origin`; addresses wrap around at 64 KiB like the offsets
+    /// of a segment do.
+    ///
+    /// # Examples
+    /// ```
+    /// use inst_decoding_8086::{Decoder, Mnemonic};
+    ///
+    /// let mnemonics: Vec<Mnemonic> = Decoder::default()
+    ///     .decode_iter(&[0xB9, 0x03, 0x00, 0xE2, 0xFE], 0x100)
+    ///     .map(|instruction| instruction.unwrap().mnemonic)
+    ///     .collect();
+    /// assert_eq!(mnemonics, [Mnemonic::Mov, Mnemonic::Loop]);
+    /// ```
+    pub fn decode_iter<'a>(&self, bytes: &'a [u8], origin: u16) -> DecodeIter<'a> {
+        DecodeIter {
+            decoder: *self,
+            bytes,
+            origin,
+            offset: 0,
+            failed: false,
+        }
+    }
+}
+
+/// An iterator decoding instructions lazily from a byte slice, created by
+/// [`Decoder::decode_iter`](Decoder::decode_iter). It ends at the end of the slice or after
+/// the first error, since nothing tells where the next instruction starts once one cannot be
+/// decoded.
+#[derive(Debug, Clone)]
+pub struct DecodeIter<'a> {
+    decoder: Decoder,
+    bytes: &'a [u8],
+    origin: u16,
+    offset: usize,
+    failed: bool,
+}
+
+impl DecodeIter<'_> {
+    /// Gets the index into the slice of the next instruction to decode, which is where decoding
+    /// can be resumed after an error
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Iterator for DecodeIter<'_> {
+    type Item = Result<Instruction, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.offset >= self.bytes.len() {
+            return None;
+        }
+        let address = self.origin.wrapping_add(self.offset as u16);
+        let instruction = self.decoder.decode(&self.bytes[self.offset..], address);
+        match &instruction {
+            Ok(instruction) => self.offset += instruction.length,
+            Err(_) => self.failed = true,
+        }
+        Some(instruction)
+    }
+}
+
+impl FusedIterator for DecodeIter<'_> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::instruction_set::{format::JumpTarget, opcodes::Mnemonic};
+
+    #[test]
+    fn test_decode_for_cpu() {
+        assert_eq!(Decoder::default().cpu(), Cpu::Intel8086);
+        assert_eq!(
+            Decoder::default().decode(&[0x60], 0),
+            Err(DecodeError::InvalidInstruction)
+        );
+        let pusha = Decoder::new(Cpu::Intel80186).decode(&[0x60], 0).unwrap();
+        assert_eq!(pusha.mnemonic, Mnemonic::Pusha);
+        assert_eq!(pusha.length, 1);
+    }
+
+    #[test]
+    fn test_decode_incomplete_instruction() {
+        assert_eq!(
+            Decoder::default().decode(&[0xB8, 0x01], 0),
+            Err(DecodeError::IncompleteInstruction)
+        );
+    }
+
+    #[test]
+    fn test_decode_iter_addresses() {
+        let bytes = [0xB9, 0x03, 0x00, 0x01, 0xC8, 0xE2, 0xFC];
+        let addresses: Vec<u16> = Decoder::default()
+            .decode_iter(&bytes, 0xFFFE)
+            .map(|instruction| instruction.unwrap().address)
+            .collect();
+        assert_eq!(addresses, [0xFFFE, 1, 3]);
+    }
+
+    #[test]
+    fn test_decode_iter_absolute_targets() {
+        // A `loop` back onto the `mov` in front of it, and a `jmp` forward past the end of the bytes
+        let bytes = [0xB9, 0x03, 0x00, 0xE2, 0xFB, 0xEB, 0x02];
+        let listing: Vec<String> = Decoder::default()
+            .decode_iter(&bytes, 0x100)
+            .map(|instruction| instruction.unwrap().format(JumpTarget::Absolute))
+            .collect();
+        assert_eq!(listing, ["mov cx, 3", "loop 256", "jmp 265"]);
+    }
+
+    #[test]
+    fn test_decode_iter_stops_after_error() {
+        let mut instructions = Decoder::default().decode_iter(&[0x90, 0x60, 0x90], 0);
+        assert_eq!(
+            instructions.next().unwrap().unwrap().mnemonic,
+            Mnemonic::Nop
+        );
+        assert_eq!(instructions.offset(), 1);
+        assert_eq!(
+            instructions.next(),
+            Some(Err(DecodeError::InvalidInstruction))
+        );
+        assert_eq!(instructions.next(), None);
+        assert_eq!(instructions.offset(), 1);
+    }
+}
@@ -0,0 +1,1617 @@
+#[cfg(feature = "x87")]
+use super::x87::{X87Instruction, WAIT};
+use super::{
+    bit::Bit,
+    cpu::Cpu,
+    errors::DecodeError,
+    instruction::Instruction,
+    opcodes::{Encoding, Mnemonic, TWO_BYTE_ESCAPE},
+    operands::{EffectiveAddress, Operand, OperandSize, Operands},
+    prefixes::Prefixes,
+    registers::Register,
+};
+
+/// The longest encoding the decoder produces after the prefixes: opcode, ModRM, 16-bit
+/// displacement and 16-bit data
+pub(crate) const MAX_INSTRUCTION_LENGTH: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodedInstruction {
+    bytes: [u8; MAX_INSTRUCTION_LENGTH],
+    length: usize,
+    address: u16,
+    prefixes: Prefixes,
+    cpu: Cpu,
+    /// Whether a `wait` in front folds into the 8087 instruction
+    #[cfg(feature = "x87")]
+    wait: bool,
+}
+
+impl EncodedInstruction {
+    /// Create a new EncodedInstruction from the leading bytes of a buffer
+    pub(crate) fn new(buffer: &[u8]) -> Self {
+        let mut bytes = [0; MAX_INSTRUCTION_LENGTH];
+        let available = buffer.len().min(MAX_INSTRUCTION_LENGTH);
+        bytes[..available].copy_from_slice(&buffer[..available]);
+        let mut instruction = EncodedInstruction {
+            bytes,
+            length: 1,
+            address: 0,
+            prefixes: Prefixes::default(),
+            cpu: Cpu::default(),
+            #[cfg(feature = "x87")]
+            wait: false,
+        };
+        instruction.length = instruction.encoded_length();
+        instruction
+    }
+
+    /// Decode the instruction at the start of `buffer`, consuming only the bytes it is made of.
+    /// The `address` of its first byte is what relative jump targets are resolved against, and
+    /// `cpu` selects the instruction set the opcode is looked up in.
+    pub(crate) fn decode(buffer: &[u8], address: u16, cpu: Cpu) -> Result<Self, DecodeError> {
+        #[cfg(feature = "x87")]
+        if let Some(instruction) = Self::decode_waiting(buffer, address, cpu) {
+            return instruction;
+        }
+        Self::decode_single(buffer, address, cpu)
+    }
+
+    /// Decode a `wait` together with the 8087 control instruction directly following it, the
+    /// encoding assemblers emit for the waiting forms such as `finit`. When the bytes behind
+    /// the `wait` are truncated but can still become a control instruction it cannot tell yet
+    /// whether they belong together.
+    #[cfg(feature = "x87")]
+    fn decode_waiting(buffer: &[u8], address: u16, cpu: Cpu) -> Option<Result<Self, DecodeError>> {
+        let (&WAIT, rest) = buffer.split_first()? else {
+            return None;
+        };
+        match Self::decode_single(rest, address, cpu) {
+            Err(DecodeError::IncompleteInstruction) if Self::is_truncated_control(rest, cpu) => {
+                Some(Err(DecodeError::IncompleteInstruction))
+            }
+            Ok(mut instruction) if instruction.x87().and_then(|x87| x87.waiting()).is_some() => {
+                instruction.wait = true;
+                instruction.length += 1;
+                Some(Ok(instruction))
+            }
+            _ => None,
+        }
+    }
+
+    /// Check whether the truncated bytes behind a `wait` can still turn out to be a control
+    /// instruction, judged by the ESC opcode and ModRM byte as far as they are there
+    #[cfg(feature = "x87")]
+    fn is_truncated_control(rest: &[u8], cpu: Cpu) -> bool {
+        let Ok((_, prefix_length)) = Prefixes::decode(rest, cpu) else {
+            return false;
+        };
+        match rest[prefix_length..] {
+            [] => prefix_length > 0,
+            // The escapes with control forms, which one depends on the missing ModRM byte
+            [opcode] => matches!(opcode, 0xD9 | 0xDB | 0xDD),
+            [opcode @ 0xD8..=0xDF, modrm, ..] => {
+                X87Instruction::decode(opcode, modrm >> 6, (modrm >> 3) & 0b111, modrm & 0b111)
+                    .and_then(|x87| x87.waiting())
+                    .is_some()
+            }
+            _ => false,
+        }
+    }
+
+    /// Decode the instruction at the start of `buffer` without looking past its opcode
+    fn decode_single(buffer: &[u8], address: u16, cpu: Cpu) -> Result<Self, DecodeError> {
+        let (prefixes, prefix_length) = Prefixes::decode(buffer, cpu)?;
+        let buffer = &buffer[prefix_length..];
+        if buffer.is_empty() {
+            return Err(DecodeError::IncompleteInstruction);
+        }
+        let mut instruction = EncodedInstruction::new(buffer);
+        instruction.address = address;
+        instruction.prefixes = prefixes;
+        instruction.cpu = cpu;
+        instruction.length = instruction.encoded_length();
+        // Missing bytes read as zero, so the opcode and ModRM byte have to be there before
+        // the fields they select can be validated
+        if buffer.len() < instruction.opcode_length() + instruction.has_modrm() as usize {
+            return Err(DecodeError::IncompleteInstruction);
+        }
+        let Some(opcode) = instruction.opcode() else {
+            return Err(DecodeError::InvalidInstruction);
+        };
+        // An address, far pointer or descriptor table register can only be loaded from a
+        // memory operand
+        if matches!(
+            instruction.encoding(),
+            Encoding::LoadAddress | Encoding::FarRegMem | Encoding::Memory
+        ) && instruction.mod_field() == 0b11
+        {
+            return Err(DecodeError::MemoryOperandRequired);
+        }
+        // The bit field instructions only work on registers
+        if matches!(
+            instruction.encoding(),
+            Encoding::ByteRegisters | Encoding::ByteRegisterWithImmediate
+        ) && instruction.mod_field() != 0b11
+        {
+            return Err(DecodeError::InvalidInstruction);
+        }
+        #[cfg(feature = "x87")]
+        if instruction.encoding() == Encoding::Escape && instruction.x87().is_none() {
+            return Err(DecodeError::InvalidInstruction);
+        }
+        // Only string instructions repeat, and only memory accesses have a segment to override
+        if prefixes.repeat.is_some() && !opcode.is_string() {
+            return Err(DecodeError::StrayPrefix);
+        }
+        if prefixes.segment.is_some()
+            && !opcode.uses_implied_memory()
+            && !instruction.has_memory_operand()
+        {
+            return Err(DecodeError::StrayPrefix);
+        }
+        if instruction.length > buffer.len() {
+            return Err(DecodeError::IncompleteInstruction);
+        }
+        instruction.length += prefix_length;
+        Ok(instruction)
+    }
+
+    /// Get the number of opcode bytes, two for `0F` on processors that use it as an escape
+    fn opcode_length(&self) -> usize {
+        if self.bytes[0] == TWO_BYTE_ESCAPE && self.cpu.has_two_byte_opcodes() {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Get the last opcode byte, which holds the direction, sign, count and width bits
+    fn opcode_byte(&self) -> u8 {
+        self.bytes[self.opcode_length() - 1]
+    }
+
+    /// Look up the opcode and the layout of the operands following the opcode bytes
+    fn lookup(&self) -> Option<(Mnemonic, Encoding)> {
+        match self.opcode_length() {
+            2 => Mnemonic::decode_two_byte_for_cpu(self.bytes[1], self.reg_field(), self.cpu),
+            _ => Mnemonic::decode_for_cpu(self.bytes[0], self.reg_field(), self.cpu),
+        }
+    }
+
+    /// Get the opcode, or `None` when the bytes encode none on the processor
+    fn opcode(&self) -> Option<Mnemonic> {
+        self.lookup().map(|(opcode, _)| opcode)
+    }
+
+    /// Get the layout of the operands following the opcode bytes. Bytes that encode no opcode
+    /// have no operands.
+    fn encoding(&self) -> Encoding {
+        self.lookup()
+            .map_or(Encoding::Implied, |(_, encoding)| encoding)
+    }
+
+    /// Check whether a ModRM byte follows the opcode byte
+    fn has_modrm(&self) -> bool {
+        matches!(
+            self.encoding(),
+            Encoding::RegMemWithReg
+                | Encoding::ImmediateToRegMem
+                | Encoding::SignExtendedImmediateToRegMem
+                | Encoding::SegmentRegister
+                | Encoding::RegMem
+                | Encoding::FarRegMem
+                | Encoding::LoadAddress
+                | Encoding::Escape
+                | Encoding::Memory
+                | Encoding::WordRegMem
+                | Encoding::WordRegWithRegMem
+                | Encoding::WordRegMemWithReg
+                | Encoding::RegMemWithImmediate
+                | Encoding::BitCl
+                | Encoding::BitImmediate
+                | Encoding::ByteRegisters
+                | Encoding::ByteRegisterWithImmediate
+                | Encoding::Shift
+                | Encoding::ShiftImmediate
+        )
+    }
+
+    /// Get the 1-bit D field
+    fn d_bit(&self) -> Bit {
+        Bit((self.opcode_byte() & 0b00000010) >> 1 == 1)
+    }
+
+    /// Get the 1-bit S field, which asks for byte data to be sign-extended to a word
+    fn s_bit(&self) -> Bit {
+        Bit((self.opcode_byte() & 0b00000010) >> 1 == 1)
+    }
+
+    /// Get the 1-bit V field, which selects a shift count of cl instead of 1
+    fn v_bit(&self) -> Bit {
+        Bit((self.opcode_byte() & 0b00000010) >> 1 == 1)
+    }
+
+    /// Get the 1-bit W field, which sits in front of the register for `....wreg` encodings.
+    /// Segment registers are always moved as words.
+    fn w_bit(&self) -> Bit {
+        match self.encoding() {
+            Encoding::ImmediateToReg => Bit((self.opcode_byte() & 0b00001000) >> 3 == 1),
+            Encoding::SegmentRegister
+            | Encoding::LoadAddress
+            | Encoding::Escape
+            | Encoding::WordRegMem
+            | Encoding::WordRegWithRegMem
+            | Encoding::WordRegMemWithReg
+            | Encoding::RegMemWithImmediate => Bit(true),
+            Encoding::ByteRegisters | Encoding::ByteRegisterWithImmediate => Bit(false),
+            _ => Bit(self.opcode_byte() & 0b00000001 == 1),
+        }
+    }
+
+    /// Get the 3-bit register field encoded in the opcode byte
+    fn opcode_reg_field(&self) -> u8 {
+        self.opcode_byte() & 0b00000111
+    }
+
+    /// Get the ModRM byte following the opcode bytes
+    fn modrm(&self) -> u8 {
+        self.bytes[self.opcode_length()]
+    }
+
+    /// Get the 2-bit mod field
+    fn mod_field(&self) -> u8 {
+        (self.modrm() & 0b11000000) >> 6
+    }
+
+    /// Get the 3-bit reg field
+    fn reg_field(&self) -> u8 {
+        (self.modrm() & 0b00111000) >> 3
+    }
+
+    /// Get the 3-bit R/M field
+    fn rm_field(&self) -> u8 {
+        self.modrm() & 0b00000111
+    }
+
+    /// Get the number of displacement bytes following the ModRM byte
+    fn displacement_length(&self) -> usize {
+        if !self.has_modrm() {
+            return 0;
+        }
+        match (self.mod_field(), self.rm_field()) {
+            (0b00, 0b110) => 2,
+            (0b00, _) => 0,
+            (0b01, _) => 1,
+            (0b10, _) => 2,
+            _ => 0,
+        }
+    }
+
+    /// Get the displacement following the ModRM byte, sign-extending 8-bit displacements
+    fn displacement(&self) -> i16 {
+        let start = self.opcode_length() + 1;
+        match self.displacement_length() {
+            1 => self.bytes[start] as i8 as i16,
+            2 => i16::from_le_bytes([self.bytes[start], self.bytes[start + 1]]),
+            _ => 0,
+        }
+    }
+
+    /// Check whether one of the operands is a memory location a segment override applies to
+    fn has_memory_operand(&self) -> bool {
+        match self.encoding() {
+            Encoding::MemoryToAccumulator | Encoding::AccumulatorToMemory => true,
+            _ => self.has_modrm() && self.mod_field() != 0b11,
+        }
+    }
+
+    /// Get the operand the mod and R/M fields select, either a register or a memory location
+    fn rm_operand(&self) -> Result<Operand, DecodeError> {
+        Ok(match (self.mod_field(), self.rm_field()) {
+            (0b11, rm) => Operand::Register(Register::from_w_and_field(self.w_bit(), rm)?),
+            (0b00, 0b110) => Operand::Memory(
+                EffectiveAddress::direct(self.displacement() as u16)
+                    .with_segment(self.prefixes.segment),
+            ),
+            (_, rm) => Operand::Memory(
+                EffectiveAddress::from_rm_field(rm, self.displacement())
+                    .with_segment(self.prefixes.segment),
+            ),
+        })
+    }
+
+    /// Get the number of immediate data bytes following the displacement
+    fn data_length(&self) -> usize {
+        match self.encoding() {
+            Encoding::ImmediateToRegMem
+            | Encoding::ImmediateToReg
+            | Encoding::ImmediateToAccumulator => {
+                if self.w_bit() == Bit(true) {
+                    2
+                } else {
+                    1
+                }
+            }
+            Encoding::SignExtendedImmediateToRegMem => {
+                if self.w_bit() == Bit(true) && self.s_bit() == Bit(false) {
+                    2
+                } else {
+                    1
+                }
+            }
+            Encoding::RegMemWithImmediate | Encoding::SignExtendedImmediate => {
+                if self.s_bit() == Bit(true) {
+                    1
+                } else {
+                    2
+                }
+            }
+            Encoding::MemoryToAccumulator | Encoding::AccumulatorToMemory => 2,
+            Encoding::ShortJump
+            | Encoding::FixedPort
+            | Encoding::Immediate8
+            | Encoding::ShiftImmediate
+            | Encoding::BitImmediate
+            | Encoding::ByteRegisterWithImmediate
+            | Encoding::Base => 1,
+            Encoding::NearJump | Encoding::Immediate16 => 2,
+            Encoding::StackFrame => 3,
+            Encoding::FarPointer => 4,
+            _ => 0,
+        }
+    }
+
+    /// Get the index of the first immediate data byte
+    fn data_start(&self) -> usize {
+        self.opcode_length() + self.has_modrm() as usize + self.displacement_length()
+    }
+
+    /// Get the immediate data, sign-extending 8-bit data so it prints as written
+    fn data(&self) -> i16 {
+        let start = self.data_start();
+        match self.data_length() {
+            1 => self.bytes[start] as i8 as i16,
+            2 => i16::from_le_bytes([self.bytes[start], self.bytes[start + 1]]),
+            _ => 0,
+        }
+    }
+
+    /// Get the size a memory operand needs when no register operand implies it
+    fn operand_size(&self) -> OperandSize {
+        if self.w_bit() == Bit(true) {
+            OperandSize::Word
+        } else {
+            OperandSize::Byte
+        }
+    }
+
+    /// Get the R/M operand, sizing memory since nothing else implies its size
+    fn sized_rm_operand(&self) -> Result<Operand, DecodeError> {
+        Ok(self.rm_operand()?.with_size(self.operand_size()))
+    }
+
+    /// Get the accumulator matching the w bit
+    fn accumulator(&self) -> Result<Operand, DecodeError> {
+        Ok(Operand::Register(Register::from_w_and_field(
+            self.w_bit(),
+            0b000,
+        )?))
+    }
+
+    /// Get the word register the reg field selects, whatever the w bit says
+    fn word_reg_operand(&self) -> Result<Operand, DecodeError> {
+        Ok(Operand::Register(Register::from_w_and_field(
+            Bit(true),
+            self.reg_field(),
+        )?))
+    }
+
+    /// Get the frame size and nesting level of an `enter`, stored in that order
+    fn stack_frame(&self) -> (u16, u8) {
+        let start = self.data_start();
+        let size = u16::from_le_bytes([self.bytes[start], self.bytes[start + 1]]);
+        (size, self.bytes[start + 2])
+    }
+
+    /// Get the 6-bit coprocessor opcode from the low bits of the ESC opcode and the reg field
+    fn escape_code(&self) -> u8 {
+        (self.bytes[0] & 0b00000111) << 3 | self.reg_field()
+    }
+
+    /// Decode the 8087 instruction an ESC opcode carries
+    #[cfg(feature = "x87")]
+    fn x87(&self) -> Option<X87Instruction> {
+        let x87 = match self.encoding() {
+            Encoding::Escape => X87Instruction::decode(
+                self.bytes[0],
+                self.mod_field(),
+                self.reg_field(),
+                self.rm_field(),
+            )?,
+            _ => return None,
+        };
+        if self.wait {
+            x87.waiting()
+        } else {
+            Some(x87)
+        }
+    }
+
+    /// Get the segment and offset of a far pointer, which is stored offset first
+    fn far_pointer(&self) -> (u16, u16) {
+        let start = self.data_start();
+        let offset = u16::from_le_bytes([self.bytes[start], self.bytes[start + 1]]);
+        let segment = u16::from_le_bytes([self.bytes[start + 2], self.bytes[start + 3]]);
+        (segment, offset)
+    }
+
+    /// Get the total number of bytes the opcode, mod and w fields call for
+    fn encoded_length(&self) -> usize {
+        self.data_start() + self.data_length()
+    }
+
+    /// Check whether the instruction relies on an opcode or prefix the 8086 does not document.
+    /// SETMO has no documented meaning to fall back on, so it is marked in every mode.
+    fn is_undocumented(&self) -> bool {
+        self.prefixes.lock_alias
+            || (self.cpu.has_undocumented_opcodes()
+                && Mnemonic::decode_undocumented(self.bytes[0], self.reg_field()).is_some())
+            || matches!(self.opcode(), Some(Mnemonic::Setmo | Mnemonic::Setmoc))
+    }
+
+    /// Build the structured instruction, with the operation and operands the encoding selects
+    pub(crate) fn decoded(&self) -> Result<Instruction, DecodeError> {
+        let Some(opcode) = self.opcode() else {
+            return Err(DecodeError::InvalidInstruction);
+        };
+        #[cfg(feature = "x87")]
+        let (mnemonic, operands) = match self.x87() {
+            Some(x87) => (
+                x87.mnemonic(),
+                x87.operands(self.rm_operand()?, self.rm_field()),
+            ),
+            None => (opcode, self.operands()?),
+        };
+        #[cfg(not(feature = "x87"))]
+        let (mnemonic, operands) = (opcode, self.operands()?);
+        Ok(Instruction {
+            mnemonic,
+            operands,
+            prefixes: self.prefixes,
+            length: self.length,
+            address: self.address,
+            undocumented: self.is_undocumented(),
+        })
+    }
+
+    /// Get the operands of the instruction in the order they are written, destination first
+    fn operands(&self) -> Result<Operands, DecodeError> {
+        Ok(match self.encoding() {
+            Encoding::RegMemWithReg => {
+                let reg =
+                    Operand::Register(Register::from_w_and_field(self.w_bit(), self.reg_field())?);
+                let rm = self.rm_operand()?;
+                if self.d_bit() == Bit(true) {
+                    Operands::from([reg, rm])
+                } else {
+                    Operands::from([rm, reg])
+                }
+            }
+            Encoding::ImmediateToRegMem | Encoding::SignExtendedImmediateToRegMem => {
+                Operands::from([
+                    self.sized_rm_operand()?,
+                    Operand::Immediate(self.data() as i32),
+                ])
+            }
+            Encoding::ImmediateToReg => {
+                let reg = Register::from_w_and_field(self.w_bit(), self.opcode_reg_field())?;
+                Operands::from([
+                    Operand::Register(reg),
+                    Operand::Immediate(self.data() as i32),
+                ])
+            }
+            Encoding::ImmediateToAccumulator => {
+                Operands::from([self.accumulator()?, Operand::Immediate(self.data() as i32)])
+            }
+            Encoding::MemoryToAccumulator | Encoding::AccumulatorToMemory => {
+                let address = Operand::Memory(
+                    EffectiveAddress::direct(self.data() as u16)
+                        .with_segment(self.prefixes.segment),
+                );
+                if self.encoding() == Encoding::MemoryToAccumulator {
+                    Operands::from([self.accumulator()?, address])
+                } else {
+                    Operands::from([address, self.accumulator()?])
+                }
+            }
+            Encoding::SegmentRegister => {
+                let sr = Operand::Register(Register::from_sr_field(self.reg_field())?);
+                let rm = self.rm_operand()?;
+                if self.d_bit() == Bit(true) {
+                    Operands::from([sr, rm])
+                } else {
+                    Operands::from([rm, sr])
+                }
+            }
+            Encoding::ShortJump | Encoding::NearJump => {
+                Operands::from([Operand::RelativeTarget(self.data())])
+            }
+            Encoding::FarPointer => {
+                let (segment, offset) = self.far_pointer();
+                Operands::from([Operand::FarPointer { segment, offset }])
+            }
+            Encoding::RegMem => Operands::from([self.sized_rm_operand()?]),
+            Encoding::Shift => {
+                let count = if self.v_bit() == Bit(true) {
+                    Operand::Register(Register::CL)
+                } else {
+                    Operand::Immediate(1)
+                };
+                Operands::from([self.sized_rm_operand()?, count])
+            }
+            Encoding::ShiftImmediate
+            | Encoding::BitImmediate
+            | Encoding::ByteRegisterWithImmediate => Operands::from([
+                self.sized_rm_operand()?,
+                Operand::Immediate(self.data() as u8 as i32),
+            ]),
+            Encoding::BitCl => {
+                Operands::from([self.sized_rm_operand()?, Operand::Register(Register::CL)])
+            }
+            Encoding::ByteRegisters => {
+                let reg = Register::from_w_and_field(Bit(false), self.reg_field())?;
+                Operands::from([self.rm_operand()?, Operand::Register(reg)])
+            }
+            Encoding::RegMemWithImmediate => Operands::from([
+                self.word_reg_operand()?,
+                self.rm_operand()?,
+                Operand::Immediate(self.data() as i32),
+            ]),
+            Encoding::FarRegMem => Operands::from([self.rm_operand()?.with_size(OperandSize::Far)]),
+            Encoding::Immediate8 | Encoding::Base => {
+                Operands::from([Operand::Immediate(self.data() as u8 as i32)])
+            }
+            Encoding::Immediate16 => {
+                Operands::from([Operand::Immediate(self.data() as u16 as i32)])
+            }
+            Encoding::SignExtendedImmediate => {
+                Operands::from([Operand::Immediate(self.data() as i32)])
+            }
+            Encoding::StackFrame => {
+                let (size, level) = self.stack_frame();
+                Operands::from([
+                    Operand::Immediate(size as i32),
+                    Operand::Immediate(level as i32),
+                ])
+            }
+            Encoding::Register => {
+                let reg = Register::from_w_and_field(Bit(true), self.opcode_reg_field())?;
+                Operands::from([Operand::Register(reg)])
+            }
+            Encoding::Escape => Operands::from([
+                Operand::Immediate(self.escape_code() as i32),
+                self.rm_operand()?,
+            ]),
+            Encoding::LoadAddress | Encoding::WordRegWithRegMem => {
+                Operands::from([self.word_reg_operand()?, self.rm_operand()?])
+            }
+            Encoding::WordRegMemWithReg => {
+                Operands::from([self.rm_operand()?, self.word_reg_operand()?])
+            }
+            Encoding::Memory | Encoding::WordRegMem => Operands::from([self.rm_operand()?]),
+            Encoding::RegisterWithAccumulator => {
+                let reg = Register::from_w_and_field(Bit(true), self.opcode_reg_field())?;
+                Operands::from([Operand::Register(Register::AX), Operand::Register(reg)])
+            }
+            Encoding::Segment => {
+                let sr = Register::from_sr_field((self.bytes[0] & 0b00011000) >> 3)?;
+                Operands::from([Operand::Register(sr)])
+            }
+            Encoding::FixedPort | Encoding::VariablePort => {
+                let port = match self.encoding() {
+                    Encoding::FixedPort => Operand::Immediate(self.data() as u8 as i32),
+                    _ => Operand::Register(Register::DX),
+                };
+                if self.opcode() == Some(Mnemonic::In) {
+                    Operands::from([self.accumulator()?, port])
+                } else {
+                    Operands::from([port, self.accumulator()?])
+                }
+            }
+            Encoding::Implied => Operands::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction_set::format::JumpTarget;
+
+    /// Decode each case on `cpu`, checking it consumes all of its bytes and formats as expected
+    fn assert_listing(cpu: Cpu, cases: &[(&[u8], &str)]) {
+        for &(bytes, expected) in cases {
+            let inst = EncodedInstruction::decode(bytes, 0, cpu).unwrap();
+            assert_eq!(inst.length, bytes.len(), "{}", expected);
+            assert_eq!(inst.decoded().unwrap().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_opcode_extraction() {
+        let inst = EncodedInstruction::new(&[0b10001011, 0b11111111]);
+        assert_eq!(inst.opcode(), Some(Mnemonic::Mov));
+    }
+
+    #[test]
+    fn test_d_bit_extraction() {
+        let inst = EncodedInstruction::new(&[0b00000010, 0b00000000]);
+        assert_eq!(inst.d_bit(), Bit(true));
+    }
+
+    #[test]
+    fn test_w_bit_extraction() {
+        let inst = EncodedInstruction::new(&[0b00000001, 0b00000000]);
+        assert_eq!(inst.w_bit(), Bit(true));
+    }
+
+    #[test]
+    fn test_mod_field_extraction() {
+        let inst = EncodedInstruction::new(&[0b00000000, 0b11000000]);
+        assert_eq!(inst.mod_field(), 0b11);
+    }
+
+    #[test]
+    fn test_reg_field_extraction() {
+        let inst = EncodedInstruction::new(&[0b00000000, 0b00111000]);
+        assert_eq!(inst.reg_field(), 0b111);
+    }
+
+    #[test]
+    fn test_rm_field_extraction() {
+        let inst = EncodedInstruction::new(&[0b00000000, 0b00000111]);
+        assert_eq!(inst.rm_field(), 0b111);
+    }
+
+    #[test]
+    fn test_decode_length_follows_mod_field() {
+        // mov with mod=11, mod=01, mod=10 and the mod=00 r/m=110 direct address
+        let cases: [(&[u8], usize); 5] = [
+            (&[0x89, 0xD9], 2),
+            (&[0x8B, 0x00], 2),
+            (&[0x8A, 0x40, 0x04], 3),
+            (&[0x8B, 0x81, 0x34, 0x12], 4),
+            (&[0x8B, 0x1E, 0x34, 0x12], 4),
+        ];
+        for (bytes, length) in cases {
+            let inst = EncodedInstruction::decode(bytes, 0, Cpu::Intel8086).unwrap();
+            assert_eq!(inst.length, length);
+            assert_eq!(&inst.bytes[..inst.length], bytes);
+        }
+    }
+
+    #[test]
+    fn test_decode_consumes_only_its_own_bytes() {
+        let inst =
+            EncodedInstruction::decode(&[0x89, 0xD9, 0x88, 0xE5], 0, Cpu::Intel8086).unwrap();
+        assert_eq!(inst.length, 2);
+        assert_eq!(inst.bytes[..inst.length], [0x89, 0xD9]);
+    }
+
+    #[test]
+    fn test_decode_incomplete_instruction() {
+        assert_eq!(
+            EncodedInstruction::decode(&[], 0, Cpu::Intel8086),
+            Err(DecodeError::IncompleteInstruction)
+        );
+        assert_eq!(
+            EncodedInstruction::decode(&[0x89], 0, Cpu::Intel8086),
+            Err(DecodeError::IncompleteInstruction)
+        );
+        assert_eq!(
+            EncodedInstruction::decode(&[0x8B, 0x81, 0x34], 0, Cpu::Intel8086),
+            Err(DecodeError::IncompleteInstruction)
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_opcode() {
+        assert_eq!(
+            EncodedInstruction::decode(&[0xFF, 0xFF], 0, Cpu::Intel8086),
+            Err(DecodeError::InvalidInstruction)
+        );
+    }
+
+    #[test]
+    fn test_invalid_instruction() {
+        let inst = EncodedInstruction::new(&[0b11111111, 0b00111000]);
+        assert_eq!(inst.decoded(), Err(DecodeError::InvalidInstruction));
+    }
+
+    #[test]
+    fn test_format_instruction_memory_operands() {
+        let cases: [(&[u8], &str); 10] = [
+            (&[0x8A, 0x00], "mov al, [bx + si]"),
+            (&[0x8B, 0x1B], "mov bx, [bp + di]"),
+            (&[0x8B, 0x56, 0x00], "mov dx, [bp]"),
+            (&[0x8A, 0x60, 0x04], "mov ah, [bx + si + 4]"),
+            (&[0x8A, 0x80, 0x87, 0x13], "mov al, [bx + si + 4999]"),
+            (&[0x89, 0x09], "mov [bx + di], cx"),
+            (&[0x88, 0x0A], "mov [bp + si], cl"),
+            (&[0x88, 0x6E, 0x00], "mov [bp], ch"),
+            (&[0x8B, 0x41, 0xDB], "mov ax, [bx + di - 37]"),
+            (&[0x89, 0x8C, 0xD4, 0xFE], "mov [si - 300], cx"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_direct_address() {
+        assert_listing(
+            Cpu::Intel8086,
+            &[
+                (&[0x8B, 0x2E, 0x05, 0x00], "mov bp, [5]"),
+                (&[0x8B, 0x1E, 0x82, 0x0D], "mov bx, [3458]"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_displacement_sign_extension() {
+        // An 8-bit displacement is sign-extended, a 16-bit one is taken as is
+        assert_listing(
+            Cpu::Intel8086,
+            &[
+                (&[0x8B, 0x57, 0xE0], "mov dx, [bx - 32]"),
+                (&[0x8B, 0x97, 0xE0, 0x00], "mov dx, [bx + 224]"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_immediate_to_register() {
+        let cases: [(&[u8], &str); 6] = [
+            (&[0xB1, 0x0C], "mov cl, 12"),
+            (&[0xB5, 0xF4], "mov ch, -12"),
+            (&[0xB9, 0x0C, 0x00], "mov cx, 12"),
+            (&[0xB9, 0xF4, 0xFF], "mov cx, -12"),
+            (&[0xBA, 0x6C, 0x0F], "mov dx, 3948"),
+            (&[0xBA, 0x94, 0xF0], "mov dx, -3948"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_immediate_to_register_memory() {
+        let cases: [(&[u8], &str); 4] = [
+            (&[0xC6, 0x03, 0x07], "mov byte [bp + di], 7"),
+            (
+                &[0xC7, 0x85, 0x85, 0x03, 0x5B, 0x01],
+                "mov word [di + 901], 347",
+            ),
+            (
+                &[0xC7, 0x83, 0x85, 0x03, 0x5B, 0x01],
+                "mov word [bp + di + 901], 347",
+            ),
+            (&[0xC7, 0xC1, 0x0C, 0x00], "mov cx, 12"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_decode_immediate_to_register_memory_invalid_reg_field() {
+        assert_eq!(
+            EncodedInstruction::decode(&[0xC7, 0x08, 0x00, 0x00], 0, Cpu::Intel8086),
+            Err(DecodeError::InvalidInstruction)
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_accumulator() {
+        let cases: [(&[u8], &str); 5] = [
+            (&[0xA1, 0xFB, 0x09], "mov ax, [2555]"),
+            (&[0xA1, 0x10, 0x00], "mov ax, [16]"),
+            (&[0xA0, 0x10, 0x00], "mov al, [16]"),
+            (&[0xA3, 0xFA, 0x09], "mov [2554], ax"),
+            (&[0xA2, 0x0F, 0x00], "mov [15], al"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_segment_register() {
+        let cases: [(&[u8], &str); 5] = [
+            (&[0x8E, 0xD8], "mov ds, ax"),
+            (&[0x8E, 0xC3], "mov es, bx"),
+            (&[0x8C, 0xD1], "mov cx, ss"),
+            (&[0x8C, 0x0F], "mov [bx], cs"),
+            (&[0x8E, 0x5E, 0xFE], "mov ds, [bp - 2]"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+        assert_eq!(
+            EncodedInstruction::decode(&[0x8E, 0xE0], 0, Cpu::Intel8086),
+            Err(DecodeError::InvalidInstruction)
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_arithmetic() {
+        let cases: [(&[u8], &str); 16] = [
+            (&[0x03, 0x18], "add bx, [bx + si]"),
+            (&[0x03, 0x5E, 0x00], "add bx, [bp]"),
+            (&[0x83, 0xC6, 0x02], "add si, 2"),
+            (&[0x83, 0xC5, 0x02], "add bp, 2"),
+            (&[0x83, 0xC1, 0x08], "add cx, 8"),
+            (&[0x00, 0x5E, 0x00], "add [bp], bl"),
+            (&[0x80, 0x07, 0x22], "add byte [bx], 34"),
+            (
+                &[0x83, 0x82, 0xE8, 0x03, 0x1D],
+                "add word [bp + si + 1000], 29",
+            ),
+            (&[0x05, 0xE8, 0x03], "add ax, 1000"),
+            (&[0x04, 0xE2], "add al, -30"),
+            (&[0x12, 0xC3], "adc al, bl"),
+            (&[0x1D, 0x01, 0x00], "sbb ax, 1"),
+            (&[0x2B, 0x1E, 0xE2, 0x12], "sub bx, [4834]"),
+            (&[0x83, 0x29, 0x1D], "sub word [bx + di], 29"),
+            (&[0x81, 0xFB, 0x00, 0x10], "cmp bx, 4096"),
+            (&[0x38, 0x0F], "cmp [bx], cl"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_sign_extended_immediate() {
+        // s=1, w=1 carries one byte which is sign-extended to a word
+        assert_listing(Cpu::Intel8086, &[(&[0x83, 0xC3, 0xFF], "add bx, -1")]);
+        // s=0, w=1 carries a full word
+        assert_listing(
+            Cpu::Intel8086,
+            &[(&[0x81, 0xC3, 0xFF, 0x00], "add bx, 255")],
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_logical() {
+        let cases: [(&[u8], &str); 15] = [
+            (&[0x21, 0xD8], "and ax, bx"),
+            (&[0x22, 0x47, 0x04], "and al, [bx + 4]"),
+            (&[0x25, 0xFF, 0x00], "and ax, 255"),
+            (&[0x80, 0x27, 0x0F], "and byte [bx], 15"),
+            (&[0x09, 0xC9], "or cx, cx"),
+            (&[0x0C, 0x80], "or al, -128"),
+            (&[0x81, 0xCA, 0x00, 0x80], "or dx, -32768"),
+            (&[0x31, 0xC0], "xor ax, ax"),
+            (&[0x81, 0x37, 0x34, 0x12], "xor word [bx], 4660"),
+            (&[0x83, 0xE0, 0x01], "and ax, 1"),
+            (&[0x83, 0x25, 0xFF], "and word [di], -1"),
+            (&[0x83, 0xC8, 0xFF], "or ax, -1"),
+            (&[0x85, 0xD8], "test ax, bx"),
+            (&[0x84, 0x4E, 0x02], "test [bp + 2], cl"),
+            (&[0xA8, 0x01], "test al, 1"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_test_immediate() {
+        assert_listing(
+            Cpu::Intel8086,
+            &[
+                (&[0xF6, 0xC3, 0x80], "test bl, -128"),
+                (&[0xF7, 0x06, 0x10, 0x00, 0x01, 0x00], "test word [16], 1"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_conditional_jumps() {
+        let mnemonics = [
+            "jo", "jno", "jb", "jnb", "jz", "jnz", "jbe", "ja", "js", "jns", "jp", "jnp", "jl",
+            "jnl", "jle", "jg",
+        ];
+        for (condition, mnemonic) in mnemonics.iter().enumerate() {
+            let inst =
+                EncodedInstruction::decode(&[0x70 | condition as u8, 0xFA], 0, Cpu::Intel8086)
+                    .unwrap();
+            assert_eq!(inst.length, 2);
+            assert_eq!(
+                inst.decoded().unwrap().to_string(),
+                format!("{} $+2-6", mnemonic)
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_instruction_loops() {
+        let cases: [(&[u8], &str); 4] = [
+            (&[0xE0, 0xFE], "loopnz $+2-2"),
+            (&[0xE1, 0x00], "loopz $+2+0"),
+            (&[0xE2, 0x7F], "loop $+2+127"),
+            (&[0xE3, 0x80], "jcxz $+2-128"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_absolute_jump_target() {
+        let inst = EncodedInstruction::decode(&[0x75, 0xFA], 10, Cpu::Intel8086).unwrap();
+        assert_eq!(
+            inst.decoded().unwrap().format(JumpTarget::Absolute),
+            "jnz 6"
+        );
+        let inst = EncodedInstruction::decode(&[0xE2, 0x10], 0x100, Cpu::Intel8086).unwrap();
+        assert_eq!(
+            inst.decoded().unwrap().format(JumpTarget::Absolute),
+            "loop 274"
+        );
+        // Targets wrap around the 64K address space like the instruction pointer does
+        let inst = EncodedInstruction::decode(&[0x72, 0xF0], 2, Cpu::Intel8086).unwrap();
+        assert_eq!(
+            inst.decoded().unwrap().format(JumpTarget::Absolute),
+            "jb 65524"
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_near_and_short_jumps() {
+        let cases: [(&[u8], &str); 4] = [
+            (&[0xE8, 0x00, 0x01], "call $+3+256"),
+            (&[0xE8, 0xFD, 0xFF], "call $+3-3"),
+            (&[0xE9, 0x9C, 0xFF], "jmp $+3-100"),
+            (&[0xEB, 0x05], "jmp $+2+5"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+        let inst = EncodedInstruction::decode(&[0xE8, 0x00, 0x01], 0x100, Cpu::Intel8086).unwrap();
+        assert_eq!(
+            inst.decoded().unwrap().format(JumpTarget::Absolute),
+            "call 515"
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_far_direct() {
+        assert_listing(
+            Cpu::Intel8086,
+            &[
+                (&[0x9A, 0x2E, 0x16, 0xD2, 0x04], "call 1234:5678"),
+                (&[0xEA, 0x00, 0x00, 0xFF, 0xFF], "jmp 65535:0"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_indirect() {
+        let cases: [(&[u8], &str); 6] = [
+            (&[0xFF, 0xD3], "call bx"),
+            (&[0xFF, 0x17], "call word [bx]"),
+            (&[0xFF, 0x1F], "call far [bx]"),
+            (&[0xFF, 0xE0], "jmp ax"),
+            (&[0xFF, 0x66, 0x04], "jmp word [bp + 4]"),
+            (&[0xFF, 0x2E, 0x34, 0x12], "jmp far [4660]"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_decode_far_indirect_through_register() {
+        // A far pointer has to be loaded from memory, like the address of lea, lds and les
+        for bytes in [[0xFF, 0xD8], [0xFF, 0xDB], [0xFF, 0xE8]] {
+            assert_eq!(
+                EncodedInstruction::decode(&bytes, 0, Cpu::Intel8086),
+                Err(DecodeError::MemoryOperandRequired)
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_instruction_returns() {
+        let cases: [(&[u8], &str); 4] = [
+            (&[0xC3], "ret"),
+            (&[0xC2, 0x04, 0x00], "ret 4"),
+            (&[0xCB], "retf"),
+            (&[0xCA, 0xFE, 0xFF], "retf 65534"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_stack() {
+        let cases: [(&[u8], &str); 14] = [
+            (&[0x50], "push ax"),
+            (&[0x55], "push bp"),
+            (&[0x5F], "pop di"),
+            (&[0x5C], "pop sp"),
+            (&[0x06], "push es"),
+            (&[0x0E], "push cs"),
+            (&[0x16], "push ss"),
+            (&[0x1F], "pop ds"),
+            (&[0x07], "pop es"),
+            (&[0xFF, 0x36, 0x10, 0x00], "push word [16]"),
+            (&[0xFF, 0xF1], "push cx"),
+            (&[0x8F, 0x47, 0x02], "pop word [bx + 2]"),
+            (&[0x9C], "pushf"),
+            (&[0x9D], "popf"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_decode_pop_memory_requires_reg_000() {
+        assert_eq!(
+            EncodedInstruction::decode(&[0x8F, 0x4F, 0x02], 0, Cpu::Intel8086),
+            Err(DecodeError::InvalidInstruction)
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_unary() {
+        let cases: [(&[u8], &str); 14] = [
+            (&[0x40], "inc ax"),
+            (&[0x4E], "dec si"),
+            (&[0xFE, 0xC0], "inc al"),
+            (&[0xFE, 0x0F], "dec byte [bx]"),
+            (&[0xFF, 0x46, 0x02], "inc word [bp + 2]"),
+            (&[0xFF, 0xC9], "dec cx"),
+            (&[0xF6, 0xD8], "neg al"),
+            (&[0xF7, 0x1F], "neg word [bx]"),
+            (&[0xF6, 0x17], "not byte [bx]"),
+            (&[0xF7, 0xE3], "mul bx"),
+            (&[0xF6, 0x2E, 0x10, 0x00], "imul byte [16]"),
+            (&[0xF7, 0xF1], "div cx"),
+            (&[0xF7, 0x7E, 0xFE], "idiv word [bp - 2]"),
+            (&[0xF6, 0xFC], "idiv ah"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_shift() {
+        let cases: [(&[u8], &str); 11] = [
+            (&[0xD1, 0xE0], "shl ax, 1"),
+            (&[0xD2, 0x1F], "rcr byte [bx], cl"),
+            (&[0xD0, 0xC4], "rol ah, 1"),
+            (&[0xD3, 0xC9], "ror cx, cl"),
+            (&[0xD1, 0x56, 0x04], "rcl word [bp + 4], 1"),
+            (&[0xD0, 0xE8], "shr al, 1"),
+            (&[0xD3, 0x3E, 0x10, 0x00], "sar word [16], cl"),
+            (&[0xD1, 0xFB], "sar bx, 1"),
+            (&[0xD2, 0xE3], "shl bl, cl"),
+            // The reg=110 alias the 8086 runs as SETMO, setting the operand to all ones
+            (&[0xD1, 0xF0], "setmo ax ; undocumented"),
+            (
+                &[0xD2, 0x36, 0x10, 0x00],
+                "setmoc byte [16], cl ; undocumented",
+            ),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_string() {
+        let cases: [(&[u8], &str); 10] = [
+            (&[0xA4], "movsb"),
+            (&[0xA5], "movsw"),
+            (&[0xA6], "cmpsb"),
+            (&[0xA7], "cmpsw"),
+            (&[0xAA], "stosb"),
+            (&[0xAB], "stosw"),
+            (&[0xAC], "lodsb"),
+            (&[0xAD], "lodsw"),
+            (&[0xAE], "scasb"),
+            (&[0xAF], "scasw"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_repeated_string() {
+        let cases: [(&[u8], &str); 6] = [
+            (&[0xF3, 0xA4], "rep movsb"),
+            (&[0xF3, 0xAB], "rep stosw"),
+            (&[0xF3, 0xAC], "rep lodsb"),
+            (&[0xF3, 0xA6], "repe cmpsb"),
+            (&[0xF2, 0xAF], "repne scasw"),
+            (&[0xF2, 0xA5], "repne movsw"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_decode_repeat_prefix_errors() {
+        // Only string instructions can be repeated
+        assert_eq!(
+            EncodedInstruction::decode(&[0xF3, 0x89, 0xD9], 0, Cpu::Intel8086),
+            Err(DecodeError::StrayPrefix)
+        );
+        // A prefix needs an instruction to apply to
+        assert_eq!(
+            EncodedInstruction::decode(&[0xF3], 0, Cpu::Intel8086),
+            Err(DecodeError::IncompleteInstruction)
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_segment_override() {
+        let cases: [(&[u8], &str); 7] = [
+            (&[0x26, 0x8B, 0x00], "mov ax, es:[bx + si]"),
+            (&[0x2E, 0x89, 0x4E, 0x02], "mov cs:[bp + 2], cx"),
+            (&[0x36, 0xA1, 0x10, 0x00], "mov ax, ss:[16]"),
+            (&[0x3E, 0xFF, 0x37], "push word ds:[bx]"),
+            (&[0x26, 0xC6, 0x06, 0x10, 0x00, 0x07], "mov byte es:[16], 7"),
+            (&[0x26, 0xA4], "es movsb"),
+            (&[0xF3, 0x2E, 0xA5], "rep cs movsw"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_lock() {
+        let cases: [(&[u8], &str); 3] = [
+            (&[0xF0, 0x01, 0x07], "lock add [bx], ax"),
+            (&[0xF0, 0x26, 0xFE, 0x07], "lock inc byte es:[bx]"),
+            (&[0xF0, 0xF3, 0xAA], "lock rep stosb"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_decode_stray_and_duplicate_prefixes() {
+        // A segment override needs a memory operand to apply to
+        assert_eq!(
+            EncodedInstruction::decode(&[0x26, 0x89, 0xD9], 0, Cpu::Intel8086),
+            Err(DecodeError::StrayPrefix)
+        );
+        assert_eq!(
+            EncodedInstruction::decode(&[0x2E, 0x75, 0x02], 0, Cpu::Intel8086),
+            Err(DecodeError::StrayPrefix)
+        );
+        // The ES:DI operand of stos, scas and ins cannot be overridden
+        for bytes in [[0x26, 0xAA], [0x2E, 0xAF]] {
+            assert_eq!(
+                EncodedInstruction::decode(&bytes, 0, Cpu::Intel8086),
+                Err(DecodeError::StrayPrefix)
+            );
+        }
+        assert_eq!(
+            EncodedInstruction::decode(&[0x26, 0x6C], 0, Cpu::Intel80186),
+            Err(DecodeError::StrayPrefix)
+        );
+        assert_eq!(
+            EncodedInstruction::decode(&[0x26, 0x3E, 0x8B, 0x00], 0, Cpu::Intel8086),
+            Err(DecodeError::DuplicatePrefix)
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_ports() {
+        let cases: [(&[u8], &str); 8] = [
+            (&[0xE4, 0x60], "in al, 0x60"),
+            (&[0xE5, 0x08], "in ax, 0x08"),
+            (&[0xE6, 0x43], "out 0x43, al"),
+            (&[0xE7, 0xFF], "out 0xff, ax"),
+            (&[0xEC], "in al, dx"),
+            (&[0xED], "in ax, dx"),
+            (&[0xEE], "out dx, al"),
+            (&[0xEF], "out dx, ax"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_interrupts() {
+        let cases: [(&[u8], &str); 6] = [
+            (&[0xCD, 0x21], "int 0x21"),
+            (&[0xCD, 0x10], "int 0x10"),
+            (&[0xCD, 0x03], "int 0x03"),
+            (&[0xCC], "int3"),
+            (&[0xCE], "into"),
+            (&[0xCF], "iret"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_single_byte() {
+        let cases: [(&[u8], &str); 22] = [
+            (&[0xF8], "clc"),
+            (&[0xF9], "stc"),
+            (&[0xF5], "cmc"),
+            (&[0xFC], "cld"),
+            (&[0xFD], "std"),
+            (&[0xFA], "cli"),
+            (&[0xFB], "sti"),
+            (&[0xF4], "hlt"),
+            (&[0x9B], "wait"),
+            (&[0x90], "nop"),
+            (&[0x9F], "lahf"),
+            (&[0x9E], "sahf"),
+            (&[0x98], "cbw"),
+            (&[0x99], "cwd"),
+            (&[0xD7], "xlatb"),
+            (&[0x2E, 0xD7], "cs xlatb"),
+            (&[0x27], "daa"),
+            (&[0x2F], "das"),
+            (&[0x37], "aaa"),
+            (&[0x3F], "aas"),
+            (&[0xF0, 0x90], "lock nop"),
+            (&[0x26, 0xA4], "es movsb"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+        assert_eq!(
+            EncodedInstruction::decode(&[0x26, 0x90], 0, Cpu::Intel8086),
+            Err(DecodeError::StrayPrefix)
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_decimal_adjust_base() {
+        let cases: [(&[u8], &str); 4] = [
+            (&[0xD4, 0x0A], "aam"),
+            (&[0xD5, 0x0A], "aad"),
+            (&[0xD4, 0x10], "aam 16"),
+            (&[0xD5, 0x07], "aad 7"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+        assert_eq!(
+            EncodedInstruction::decode(&[0xD4], 0, Cpu::Intel8086),
+            Err(DecodeError::IncompleteInstruction)
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_exchange() {
+        let cases: [(&[u8], &str); 6] = [
+            (&[0x86, 0xC3], "xchg al, bl"),
+            (&[0x87, 0x0F], "xchg cx, [bx]"),
+            (&[0x86, 0x66, 0xFE], "xchg ah, [bp - 2]"),
+            (&[0xF0, 0x87, 0x07], "lock xchg ax, [bx]"),
+            (&[0x91], "xchg ax, cx"),
+            (&[0x97], "xchg ax, di"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_format_instruction_load_address() {
+        let cases: [(&[u8], &str); 5] = [
+            (&[0x8D, 0x40, 0x04], "lea ax, [bx + si + 4]"),
+            (&[0x8D, 0x1E, 0x82, 0x0D], "lea bx, [3458]"),
+            (&[0xC5, 0x37], "lds si, [bx]"),
+            (&[0xC4, 0x7E, 0xFA], "les di, [bp - 6]"),
+            (&[0x26, 0xC5, 0x14], "lds dx, es:[si]"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[test]
+    fn test_decode_load_address_requires_memory() {
+        for bytes in [[0x8D, 0xC0], [0xC4, 0xD8], [0xC5, 0xFF]] {
+            assert_eq!(
+                EncodedInstruction::decode(&bytes, 0, Cpu::Intel8086),
+                Err(DecodeError::MemoryOperandRequired)
+            );
+        }
+    }
+
+    #[cfg(not(feature = "x87"))]
+    #[test]
+    fn test_format_instruction_escape() {
+        let cases: [(&[u8], &str); 4] = [
+            (&[0xD8, 0x07], "esc 0, [bx]"),
+            (&[0xD9, 0xE8], "esc 13, ax"),
+            (&[0xDD, 0x5E, 0xF8], "esc 43, [bp - 8]"),
+            (&[0x9B], "wait"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+    }
+
+    #[cfg(feature = "x87")]
+    #[test]
+    fn test_format_instruction_x87() {
+        let cases: [(&[u8], &str); 32] = [
+            (&[0xD9, 0x07], "fld dword [bx]"),
+            (&[0xDD, 0x46, 0xF8], "fld qword [bp - 8]"),
+            (&[0xDB, 0x2E, 0x00, 0x01], "fld tword [256]"),
+            (&[0xD9, 0xC1], "fld st(1)"),
+            (&[0xDD, 0x1C], "fstp qword [si]"),
+            (&[0xDB, 0x3C], "fstp tword [si]"),
+            (&[0xDD, 0xDA], "fstp st(2)"),
+            (&[0xD8, 0x04], "fadd dword [si]"),
+            (&[0xD8, 0xC3], "fadd st(0), st(3)"),
+            (&[0xDC, 0xC3], "fadd st(3), st(0)"),
+            (&[0xDE, 0xC1], "faddp st(1), st(0)"),
+            (&[0xDC, 0x0F], "fmul qword [bx]"),
+            (&[0xDE, 0x0F], "fimul word [bx]"),
+            (&[0xDA, 0x5D, 0x02], "ficomp dword [di + 2]"),
+            (&[0xD8, 0xD9], "fcomp st(0), st(1)"),
+            (&[0xDE, 0xD9], "fcompp"),
+            (&[0xDC, 0xE9], "fsub st(1), st(0)"),
+            (&[0xDE, 0xE1], "fsubrp st(1), st(0)"),
+            (&[0xDF, 0x3F], "fistp qword [bx]"),
+            (&[0xD9, 0xE8], "fld1"),
+            (&[0xD9, 0xFA], "fsqrt"),
+            (&[0xDB, 0xE3], "fninit"),
+            (&[0xDD, 0x3E, 0x10, 0x00], "fnstsw word [16]"),
+            (&[0x26, 0xD9, 0x37], "fnstenv es:[bx]"),
+            (&[0x9B, 0xDB, 0xE3], "finit"),
+            (&[0x9B, 0xDB, 0xE2], "fclex"),
+            (&[0x9B, 0xDB, 0xE0], "feni"),
+            (&[0x9B, 0xDB, 0xE1], "fdisi"),
+            (&[0x9B, 0xDD, 0x3E, 0x10, 0x00], "fstsw word [16]"),
+            (&[0x9B, 0xD9, 0x3F], "fstcw word [bx]"),
+            (&[0x9B, 0x26, 0xD9, 0x37], "fstenv es:[bx]"),
+            (&[0x9B, 0xDD, 0x36, 0x00, 0x02], "fsave [512]"),
+        ];
+        assert_listing(Cpu::Intel8086, &cases);
+        // Only a control instruction directly following it absorbs a wait
+        for bytes in [&[0x9B, 0xD9, 0xC1][..], &[0x9B, 0x9B, 0xDB, 0xE3], &[0x9B]] {
+            let inst = EncodedInstruction::decode(bytes, 0, Cpu::Intel8086).unwrap();
+            assert_eq!(inst.length, 1);
+            assert_eq!(inst.decoded().unwrap().to_string(), "wait");
+        }
+        // Truncated bytes hold the wait back only while they can still become a control
+        // instruction, a complete wait is decoded on its own otherwise
+        for bytes in [&[0x9B, 0xDB][..], &[0x9B, 0x26], &[0x9B, 0xD9, 0x3E, 0x10]] {
+            assert_eq!(
+                EncodedInstruction::decode(bytes, 0, Cpu::Intel8086),
+                Err(DecodeError::IncompleteInstruction)
+            );
+        }
+        for bytes in [
+            &[0x9B, 0x89][..],
+            &[0x9B, 0xD9, 0x06, 0x10],
+            &[0x9B, 0xB8, 0x01],
+        ] {
+            let inst = EncodedInstruction::decode(bytes, 0, Cpu::Intel8086).unwrap();
+            assert_eq!(inst.length, 1);
+            assert_eq!(
+                EncodedInstruction::decode(&bytes[1..], 1, Cpu::Intel8086),
+                Err(DecodeError::IncompleteInstruction)
+            );
+        }
+        for bytes in [[0xD9, 0x08], [0xDA, 0xC0], [0xDF, 0xE0], [0xDD, 0xC8]] {
+            assert_eq!(
+                EncodedInstruction::decode(&bytes, 0, Cpu::Intel8086),
+                Err(DecodeError::InvalidInstruction)
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_instruction_80186() {
+        let cases: [(&[u8], &str); 19] = [
+            (&[0x60], "pusha"),
+            (&[0x61], "popa"),
+            (&[0x62, 0x07], "bound ax, [bx]"),
+            (&[0x68, 0x34, 0x12], "push 4660"),
+            (&[0x6A, 0xFE], "push -2"),
+            (&[0x69, 0xC3, 0xE8, 0x03], "imul ax, bx, 1000"),
+            (&[0x6B, 0x4E, 0x02, 0xFD], "imul cx, [bp + 2], -3"),
+            (&[0x6C], "insb"),
+            (&[0xF3, 0x6D], "rep insw"),
+            (&[0x2E, 0x6E], "cs outsb"),
+            (&[0x6F], "outsw"),
+            (&[0xC0, 0xE0, 0x04], "shl al, 4"),
+            (&[0xC1, 0x3F, 0x0F], "sar word [bx], 15"),
+            (&[0xC0, 0x06, 0x10, 0x00, 0x01], "rol byte [16], 1"),
+            (&[0xC8, 0x10, 0x00, 0x00], "enter 16, 0"),
+            (&[0xC8, 0x00, 0x01, 0x02], "enter 256, 2"),
+            (&[0xC9], "leave"),
+            (&[0xD1, 0xF0], "sal ax, 1"),
+            (&[0x89, 0xD9], "mov cx, bx"),
+        ];
+        assert_listing(Cpu::Intel80186, &cases);
+        assert_eq!(
+            EncodedInstruction::decode(&[0x62, 0xC0], 0, Cpu::Intel80186),
+            Err(DecodeError::MemoryOperandRequired)
+        );
+        assert_eq!(
+            EncodedInstruction::decode(&[0xC8, 0x10, 0x00], 0, Cpu::Intel80186),
+            Err(DecodeError::IncompleteInstruction)
+        );
+    }
+
+    #[test]
+    fn test_decode_80186_opcodes_on_8086() {
+        for bytes in [[0x60, 0x00], [0x68, 0x00], [0xC1, 0xE0], [0xC8, 0x00]] {
+            assert_eq!(
+                EncodedInstruction::decode(&bytes, 0, Cpu::Intel8086),
+                Err(DecodeError::InvalidInstruction)
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_instruction_80286() {
+        let cases: [(&[u8], &str); 18] = [
+            (&[0x0F, 0x00, 0xC0], "sldt ax"),
+            (&[0x0F, 0x00, 0x0F], "str [bx]"),
+            (&[0x0F, 0x00, 0xD0], "lldt ax"),
+            (&[0x0F, 0x00, 0x5E, 0x04], "ltr [bp + 4]"),
+            (&[0x0F, 0x00, 0xE3], "verr bx"),
+            (&[0x0F, 0x00, 0x2E, 0x34, 0x12], "verw [4660]"),
+            (&[0x0F, 0x01, 0x07], "sgdt [bx]"),
+            (&[0x0F, 0x01, 0x0C], "sidt [si]"),
+            (&[0x2E, 0x0F, 0x01, 0x16, 0x00, 0x01], "lgdt cs:[256]"),
+            (&[0x0F, 0x01, 0x1D], "lidt [di]"),
+            (&[0x0F, 0x01, 0xE0], "smsw ax"),
+            (&[0x0F, 0x01, 0xF0], "lmsw ax"),
+            (&[0x0F, 0x02, 0xC3], "lar ax, bx"),
+            (&[0x0F, 0x03, 0x4F, 0xFE], "lsl cx, [bx - 2]"),
+            (&[0x0F, 0x06], "clts"),
+            (&[0x63, 0xD8], "arpl ax, bx"),
+            (&[0x63, 0x07], "arpl [bx], ax"),
+            (&[0x60], "pusha"),
+        ];
+        assert_listing(Cpu::Intel80286, &cases);
+        assert_eq!(
+            EncodedInstruction::decode(&[0x0F, 0x01, 0xD0], 0, Cpu::Intel80286),
+            Err(DecodeError::MemoryOperandRequired)
+        );
+        assert_eq!(
+            EncodedInstruction::decode(&[0x0F, 0x00, 0xF0], 0, Cpu::Intel80286),
+            Err(DecodeError::InvalidInstruction)
+        );
+        assert_eq!(
+            EncodedInstruction::decode(&[0x0F], 0, Cpu::Intel80286),
+            Err(DecodeError::IncompleteInstruction)
+        );
+        assert_eq!(
+            EncodedInstruction::decode(&[0x0F, 0x06], 0, Cpu::Intel80186),
+            Err(DecodeError::InvalidInstruction)
+        );
+    }
+
+    #[test]
+    fn test_format_instruction_v20() {
+        let cases: [(&[u8], &str); 18] = [
+            (&[0x0F, 0x10, 0xC3], "test1 bl, cl"),
+            (&[0x0F, 0x11, 0x07], "test1 word [bx], cl"),
+            (&[0x0F, 0x12, 0xC0], "clr1 al, cl"),
+            (&[0x0F, 0x15, 0x46, 0x02], "set1 word [bp + 2], cl"),
+            (&[0x0F, 0x16, 0x04], "not1 byte [si], cl"),
+            (&[0x0F, 0x18, 0xC0, 0x07], "test1 al, 7"),
+            (&[0x0F, 0x1B, 0xC1, 0x0F], "clr1 cx, 15"),
+            (&[0x0F, 0x1C, 0x06, 0x10, 0x00, 0x03], "set1 byte [16], 3"),
+            (&[0x0F, 0x1F, 0xC2, 0x00], "not1 dx, 0"),
+            (&[0x0F, 0x20], "add4s"),
+            (&[0x0F, 0x22], "sub4s"),
+            (&[0x0F, 0x26], "cmp4s"),
+            (&[0x0F, 0x28, 0xC0], "rol4 al"),
+            (&[0x0F, 0x2A, 0x07], "ror4 byte [bx]"),
+            (&[0x0F, 0x31, 0xC8], "ins al, cl"),
+            (&[0x0F, 0x3B, 0xC3, 0x08], "ext bl, 8"),
+            (&[0x0F, 0xFF, 0x40], "brkem 0x40"),
+            (&[0x6A, 0x01], "push 1"),
+        ];
+        assert_listing(Cpu::NecV20, &cases);
+        for bytes in [&[0x0F][..], &[0x0F, 0x31], &[0x0F, 0x33], &[0xF0, 0x0F]] {
+            assert_eq!(
+                EncodedInstruction::decode(bytes, 0, Cpu::NecV20),
+                Err(DecodeError::IncompleteInstruction)
+            );
+        }
+        for bytes in [[0x0F, 0x31, 0x07, 0x00], [0x0F, 0x01, 0x07, 0x00]] {
+            assert_eq!(
+                EncodedInstruction::decode(&bytes, 0, Cpu::NecV20),
+                Err(DecodeError::InvalidInstruction)
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_instruction_undocumented() {
+        let cases: [(&[u8], &str); 14] = [
+            (&[0x0F], "pop cs ; undocumented"),
+            (&[0xD6], "salc ; undocumented"),
+            (&[0xD0, 0xF0], "setmo al ; undocumented"),
+            (&[0xD3, 0x37], "setmoc word [bx], cl ; undocumented"),
+            (&[0x60, 0x02], "jo $+2+2 ; undocumented"),
+            (&[0x65, 0xFC], "jnz $+2-4 ; undocumented"),
+            (&[0xC0, 0x04, 0x00], "ret 4 ; undocumented"),
+            (&[0xC1], "ret ; undocumented"),
+            (&[0xC8, 0x02, 0x00], "retf 2 ; undocumented"),
+            (&[0xC9], "retf ; undocumented"),
+            (&[0x82, 0xC3, 0xFF], "add bl, -1 ; undocumented"),
+            (&[0x82, 0x0F, 0x10], "or byte [bx], 16 ; undocumented"),
+            (&[0xF1, 0x01, 0x07], "lock add [bx], ax ; undocumented"),
+            (&[0xF0, 0x01, 0x07], "lock add [bx], ax"),
+        ];
+        assert_listing(Cpu::Intel8086Silicon, &cases);
+        for bytes in [[0x0F, 0x00], [0xD6, 0x00], [0x82, 0xC3], [0xF1, 0x90]] {
+            assert_eq!(
+                EncodedInstruction::decode(&bytes, 0, Cpu::Intel8086),
+                Err(DecodeError::InvalidInstruction)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decoded_operands() {
+        // add word es:[bp + di + 4], -3
+        let inst =
+            EncodedInstruction::decode(&[0x26, 0x83, 0x43, 0x04, 0xFD], 0x10, Cpu::Intel8086)
+                .unwrap();
+        let decoded = inst.decoded().unwrap();
+        assert_eq!(decoded.mnemonic, Mnemonic::Add);
+        assert_eq!(
+            decoded.operands,
+            [
+                Operand::Memory(
+                    EffectiveAddress::from_rm_field(0b011, 4)
+                        .with_segment(Some(Register::ES))
+                        .with_size(OperandSize::Word)
+                ),
+                Operand::Immediate(-3),
+            ]
+        );
+        assert_eq!(decoded.prefixes.segment, Some(Register::ES));
+        assert_eq!(decoded.length, 5);
+        assert_eq!(decoded.address, 0x10);
+        assert!(!decoded.undocumented);
+    }
+
+    #[test]
+    fn test_instruction_mov_all_combinations() {
+        let mut count = 0;
+        for src in 0b000..=0b111 {
+            for dest in 0b000..=0b111 {
+                for d in 0..=1u8 {
+                    for w_msb in 0..=1 {
+                        count += 1;
+                        let w = Bit(w_msb == 1);
+                        let mod_bits = 0b11;
+                        let opcode = 0b100010;
+
+                        let first_byte = (opcode << 2) | (d << 1) | w.0 as u8;
+                        let second_byte = (mod_bits << 6) | (src << 3) | dest;
+
+                        let inst = EncodedInstruction::new(&[first_byte, second_byte]);
+
+                        // Extract the string representation for the src and dest based on the W bit.
+                        let src_register = Register::from_w_and_field(w, src).unwrap();
+                        let dest_register = Register::from_w_and_field(w, dest).unwrap();
+                        let mnemonic = inst.decoded().unwrap().to_string();
+                        if d == 0 {
+                            assert_eq!(
+                                mnemonic,
+                                format!("mov {}, {}", dest_register, src_register)
+                            );
+                        } else {
+                            assert_eq!(
+                                mnemonic,
+                                format!("mov {}, {}", src_register, dest_register)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(count, 8 * 8 * 2 * 2); // all registers with combined with each other 8 * 8 , 2 for d bit, 2 for w bit
+    }
+
+    #[test]
+    fn test_debug_trait() {
+        let inst = EncodedInstruction::new(&[0b10001011, 0b11111111]);
+        let debug_string = format!("{:?}", inst);
+        assert!(!debug_string.is_empty()); // Make sure it produces a non-empty string.
+    }
+
+    #[test]
+    fn test_copy_trait() {
+        let inst1 = EncodedInstruction::new(&[0b10001011, 0b11111111]);
+        let inst2 = inst1; // This would move `inst1` if it were not `Copy`
+        assert_eq!(inst1.bytes, inst2.bytes); // Ensure they are identical
+    }
+
+    #[test]
+    fn test_clone_trait() {
+        let inst1 = EncodedInstruction::new(&[0b10001011, 0b11111111]);
+        #[allow(clippy::clone_on_copy)]
+        let inst2 = inst1.clone();
+        assert_eq!(inst1, inst2); // Ensure they are identical
+        assert!(!std::ptr::eq(&inst1, &inst2)); // Ensure they are different objects
+    }
+}
@@ -2,23 +2,35 @@ use std::error::Error;
 use std::fmt;
 use std::io;
 
+/// Why bytes could not be decoded into an instruction
 #[derive(Debug, PartialEq)]
-pub enum X86InstructionError {
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The opcode is not part of the instruction set of the selected processor
     InvalidInstruction,
+    /// A register field selects no register
     InvalidRegister,
+    /// The bytes end before the instruction does
+    IncompleteInstruction,
+    /// A prefix of the same kind appears twice in front of one opcode
+    DuplicatePrefix,
+    /// A segment override in front of an instruction without a memory operand
+    StrayPrefix,
+    /// A register operand where the instruction only accepts memory
+    MemoryOperandRequired,
 }
 
-impl fmt::Display for X86InstructionError {
+impl fmt::Display for DecodeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
-impl Error for X86InstructionError {}
+impl Error for DecodeError {}
 
-impl From<X86InstructionError> for io::Error {
-    fn from(error: X86InstructionError) -> Self {
-        io::Error::new(io::ErrorKind::Other, error)
+impl From<DecodeError> for io::Error {
+    fn from(error: DecodeError) -> Self {
+        io::Error::other(error)
     }
 }
 
@@ -28,73 +40,83 @@ mod test {
 
     #[test]
     fn test_error_conversion() {
-        let custom_error = X86InstructionError::InvalidInstruction; // Replace with an actual error variant from your X86InstructionError enum
+        let custom_error = DecodeError::InvalidInstruction; // Replace with an actual error variant from your DecodeError enum
         let io_error: io::Error = custom_error.into();
         assert_eq!(io_error.kind(), io::ErrorKind::Other);
         assert_eq!(
             io_error
                 .get_ref()
                 .unwrap()
-                .downcast_ref::<X86InstructionError>()
+                .downcast_ref::<DecodeError>()
                 .unwrap(),
-            &X86InstructionError::InvalidInstruction
+            &DecodeError::InvalidInstruction
         );
     }
 
     #[test]
     fn test_fmt() {
         assert_eq!(
-            format!("{}", X86InstructionError::InvalidInstruction),
+            format!("{}", DecodeError::InvalidInstruction),
             "InvalidInstruction"
         );
         assert_eq!(
-            format!("{}", X86InstructionError::InvalidRegister),
+            format!("{}", DecodeError::InvalidRegister),
             "InvalidRegister"
         );
+        assert_eq!(
+            format!("{}", DecodeError::IncompleteInstruction),
+            "IncompleteInstruction"
+        );
+        assert_eq!(
+            format!("{}", DecodeError::DuplicatePrefix),
+            "DuplicatePrefix"
+        );
+        assert_eq!(format!("{}", DecodeError::StrayPrefix), "StrayPrefix");
+        assert_eq!(
+            format!("{}", DecodeError::MemoryOperandRequired),
+            "MemoryOperandRequired"
+        );
     }
 
     #[test]
     fn test_debug_trait() {
-        let err = X86InstructionError::InvalidInstruction;
+        let err = DecodeError::InvalidInstruction;
         assert_eq!(format!("{:?}", err), "InvalidInstruction");
 
-        let err = X86InstructionError::InvalidRegister;
+        let err = DecodeError::InvalidRegister;
         assert_eq!(format!("{:?}", err), "InvalidRegister");
     }
 
     #[test]
     fn test_partial_eq_trait() {
         assert_eq!(
-            X86InstructionError::InvalidInstruction,
-            X86InstructionError::InvalidInstruction
-        );
-        assert_eq!(
-            X86InstructionError::InvalidRegister,
-            X86InstructionError::InvalidRegister
+            DecodeError::InvalidInstruction,
+            DecodeError::InvalidInstruction
         );
+        assert_eq!(DecodeError::InvalidRegister, DecodeError::InvalidRegister);
         assert_ne!(
-            X86InstructionError::InvalidInstruction,
-            X86InstructionError::InvalidRegister
+            DecodeError::InvalidInstruction,
+            DecodeError::InvalidRegister
         );
     }
 
     #[test]
     fn test_from_trait_implementation() {
-        let x86_error = X86InstructionError::InvalidInstruction;
+        let x86_error = DecodeError::InvalidInstruction;
         let io_error = io::Error::from(x86_error);
         assert_eq!(io_error.kind(), io::ErrorKind::Other);
         let inner = io_error.get_ref().unwrap();
-        let inner_downcasted = inner.downcast_ref::<X86InstructionError>().unwrap();
-        assert_eq!(inner_downcasted, &X86InstructionError::InvalidInstruction);
+        let inner_downcasted = inner.downcast_ref::<DecodeError>().unwrap();
+        assert_eq!(inner_downcasted, &DecodeError::InvalidInstruction);
     }
 
     #[test]
     fn test_into_trait_implementation() {
-        let x86_error = X86InstructionError::InvalidRegister;
+        let x86_error = DecodeError::InvalidRegister;
         let io_error: io::Error = x86_error.into();
         assert_eq!(io_error.kind(), io::ErrorKind::Other);
         let inner = io_error.get_ref().unwrap();
-        let inner_downcasted = inner.downcast_ref::<X86InstructionError>().unwrap();
-        assert_eq!(inner_downcasted, &X86InstructionError::InvalidRegister);
+        let inner_downcasted = inner.downcast_ref::<DecodeError>().unwrap();
+        assert_eq!(inner_downcasted, &DecodeError::InvalidRegister);
     }
 }
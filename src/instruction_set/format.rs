@@ -0,0 +1,157 @@
+use std::fmt;
+
+use super::{instruction::Instruction, opcodes::Mnemonic, operands::Operand, prefixes::Repeat};
+
+/// How the targets of relative jumps are printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JumpTarget {
+    /// Offsets from the address of the jump NASM can reassemble, such as `$+2-6`
+    #[default]
+    Relative,
+    /// Addresses counted from the `origin` the instructions were decoded at, as passed to
+    /// [`Decoder::decode_iter`](super::Decoder::decode_iter)
+    Absolute,
+}
+
+impl Instruction {
+    /// Format the instruction as NASM-style assembly, printing relative jump targets as
+    /// `jump_target` asks for. Undocumented instructions are marked with a comment so they
+    /// stand out in a listing.
+    pub fn format(&self, jump_target: JumpTarget) -> String {
+        let mut text = String::new();
+        if self.prefixes.lock {
+            text.push_str("lock ");
+        }
+        match self.prefixes.repeat {
+            Some(Repeat::Rep) if self.mnemonic.is_comparison() => text.push_str("repe "),
+            Some(Repeat::Rep) => text.push_str("rep "),
+            Some(Repeat::Repne) => text.push_str("repne "),
+            None => {}
+        }
+        // String instructions and xlatb have no operand to attach a segment override to
+        if let (Some(segment), true) = (self.prefixes.segment, self.mnemonic.uses_implied_memory())
+        {
+            text.push_str(&format!("{} ", segment));
+        }
+        text.push_str(&self.mnemonic.to_string());
+        if !self.operands.is_empty() && !self.has_default_base() {
+            let operands: Vec<String> = self
+                .operands
+                .iter()
+                .map(|operand| self.format_operand(operand, jump_target))
+                .collect();
+            text.push(' ');
+            text.push_str(&operands.join(", "));
+        }
+        if self.undocumented {
+            text.push_str(" ; undocumented");
+        }
+        text
+    }
+
+    /// Format a single operand. Port and interrupt numbers are printed in hexadecimal.
+    fn format_operand(&self, operand: &Operand, jump_target: JumpTarget) -> String {
+        match (operand, jump_target) {
+            (Operand::Immediate(value), _) if self.has_hexadecimal_immediate() => {
+                format!("{:#04x}", value)
+            }
+            (Operand::RelativeTarget(displacement), JumpTarget::Relative) => {
+                format!("$+{}{:+}", self.length, displacement)
+            }
+            (Operand::RelativeTarget(displacement), JumpTarget::Absolute) => {
+                format!("{}", self.next_address().wrapping_add(*displacement as u16))
+            }
+            (operand, _) => operand.to_string(),
+        }
+    }
+
+    /// Check whether the immediate operand numbers a port or an interrupt
+    fn has_hexadecimal_immediate(&self) -> bool {
+        matches!(
+            self.mnemonic,
+            Mnemonic::In | Mnemonic::Out | Mnemonic::Int | Mnemonic::Brkem
+        )
+    }
+
+    /// Check whether a decimal adjustment uses the documented base 10, which goes unwritten
+    fn has_default_base(&self) -> bool {
+        matches!(self.mnemonic, Mnemonic::Aam | Mnemonic::Aad)
+            && self.operands == [Operand::Immediate(10)]
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.format(JumpTarget::default()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::instruction_set::{
+        operands::{EffectiveAddress, Operands},
+        prefixes::Prefixes,
+        registers::Register,
+    };
+
+    fn instruction(mnemonic: Mnemonic, operands: Operands) -> Instruction {
+        Instruction {
+            mnemonic,
+            operands,
+            prefixes: Prefixes::default(),
+            length: 2,
+            address: 8,
+            undocumented: false,
+        }
+    }
+
+    #[test]
+    fn test_format_operands() {
+        let mov = instruction(
+            Mnemonic::Mov,
+            Operands::from([
+                Operand::Register(Register::CX),
+                Operand::Memory(EffectiveAddress::from_rm_field(0b000, -2)),
+            ]),
+        );
+        assert_eq!(mov.format(JumpTarget::Relative), "mov cx, [bx + si - 2]");
+        let nop = instruction(Mnemonic::Nop, Operands::default());
+        assert_eq!(nop.to_string(), "nop");
+    }
+
+    #[test]
+    fn test_format_relative_target() {
+        let jump = instruction(Mnemonic::Jnz, Operands::from([Operand::RelativeTarget(-6)]));
+        assert_eq!(jump.format(JumpTarget::Relative), "jnz $+2-6");
+        assert_eq!(jump.format(JumpTarget::Absolute), "jnz 4");
+        assert_eq!(jump.to_string(), "jnz $+2-6");
+    }
+
+    #[test]
+    fn test_format_hexadecimal_immediates() {
+        let int = instruction(Mnemonic::Int, Operands::from([Operand::Immediate(0x21)]));
+        assert_eq!(int.to_string(), "int 0x21");
+        let push = instruction(Mnemonic::Push, Operands::from([Operand::Immediate(33)]));
+        assert_eq!(push.to_string(), "push 33");
+    }
+
+    #[test]
+    fn test_format_default_base() {
+        let aam = instruction(Mnemonic::Aam, Operands::from([Operand::Immediate(10)]));
+        assert_eq!(aam.to_string(), "aam");
+        let aad = instruction(Mnemonic::Aad, Operands::from([Operand::Immediate(16)]));
+        assert_eq!(aad.to_string(), "aad 16");
+    }
+
+    #[test]
+    fn test_format_prefixes_and_marker() {
+        let mut movsb = instruction(Mnemonic::Movsb, Operands::default());
+        movsb.prefixes.lock = true;
+        movsb.prefixes.repeat = Some(Repeat::Rep);
+        movsb.prefixes.segment = Some(Register::ES);
+        movsb.undocumented = true;
+        assert_eq!(movsb.to_string(), "lock rep es movsb ; undocumented");
+    }
+}
@@ -1,8 +1,23 @@
 mod bit;
-mod errors;
+mod cpu;
+mod decoder;
+mod encoded;
+pub(crate) mod errors;
+mod format;
 mod instruction;
 mod opcodes;
+mod operands;
+mod prefixes;
 mod registers;
+#[cfg(feature = "x87")]
+mod x87;
 
-pub use errors::X86InstructionError;
-pub use instruction::X86Instruction;
+pub use cpu::Cpu;
+pub use decoder::{DecodeIter, Decoder};
+pub use errors::DecodeError;
+pub use format::JumpTarget;
+pub use instruction::Instruction;
+pub use opcodes::Mnemonic;
+pub use operands::{EffectiveAddress, Operand, OperandSize, Operands};
+pub use prefixes::{Prefixes, Repeat};
+pub use registers::Register;
@@ -1,26 +1,1096 @@
 use std::fmt;
 
-#[repr(u8)]
-#[derive(Debug, PartialEq, Eq)]
-pub(crate) enum X86Opcode {
-    Mov = 0b100010,
-    InvalidInstruction,
+use super::cpu::Cpu;
+
+/// The operation of an instruction, displayed as its lowercase mnemonic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Mnemonic {
+    /// Copy a value
+    Mov,
+    /// Add
+    Add,
+    /// Add with carry
+    Adc,
+    /// Subtract
+    Sub,
+    /// Subtract with borrow
+    Sbb,
+    /// Compare by subtracting, setting only the flags
+    Cmp,
+    /// Bitwise and
+    And,
+    /// Bitwise or
+    Or,
+    /// Bitwise exclusive or
+    Xor,
+    /// Bitwise and, setting only the flags
+    Test,
+    /// Jump if overflow
+    Jo,
+    /// Jump if not overflow
+    Jno,
+    /// Jump if below (carry)
+    Jb,
+    /// Jump if not below (no carry)
+    Jnb,
+    /// Jump if zero
+    Jz,
+    /// Jump if not zero
+    Jnz,
+    /// Jump if below or equal
+    Jbe,
+    /// Jump if above
+    Ja,
+    /// Jump if sign
+    Js,
+    /// Jump if not sign
+    Jns,
+    /// Jump if parity even
+    Jp,
+    /// Jump if parity odd
+    Jnp,
+    /// Jump if less
+    Jl,
+    /// Jump if not less
+    Jnl,
+    /// Jump if less or equal
+    Jle,
+    /// Jump if greater
+    Jg,
+    /// Decrement CX and jump if it is not zero and ZF is clear
+    Loopnz,
+    /// Decrement CX and jump if it is not zero and ZF is set
+    Loopz,
+    /// Decrement CX and jump if it is not zero
+    Loop,
+    /// Jump if CX is zero
+    Jcxz,
+    /// Call a procedure
+    Call,
+    /// Jump unconditionally
+    Jmp,
+    /// Return from a near procedure
+    Ret,
+    /// Return from a far procedure
+    Retf,
+    /// Push a word onto the stack
+    Push,
+    /// Pop a word off the stack
+    Pop,
+    /// Push the flags
+    Pushf,
+    /// Pop the flags
+    Popf,
+    /// Increment by one
+    Inc,
+    /// Decrement by one
+    Dec,
+    /// Negate (two's complement)
+    Neg,
+    /// Invert every bit
+    Not,
+    /// Unsigned multiply
+    Mul,
+    /// Signed multiply
+    Imul,
+    /// Unsigned divide
+    Div,
+    /// Signed divide
+    Idiv,
+    /// Rotate left
+    Rol,
+    /// Rotate right
+    Ror,
+    /// Rotate left through carry
+    Rcl,
+    /// Rotate right through carry
+    Rcr,
+    /// Shift left
+    Shl,
+    /// Logical shift right
+    Shr,
+    /// Arithmetic shift left, the alias of `shl` the 80186 runs the undocumented reg=110 shift as
+    Sal,
+    /// Arithmetic shift right
+    Sar,
+    /// Move a string byte
+    Movsb,
+    /// Move a string word
+    Movsw,
+    /// Compare string bytes
+    Cmpsb,
+    /// Compare string words
+    Cmpsw,
+    /// Scan a string for AL
+    Scasb,
+    /// Scan a string for AX
+    Scasw,
+    /// Load a string byte into AL
+    Lodsb,
+    /// Load a string word into AX
+    Lodsw,
+    /// Store AL into a string byte
+    Stosb,
+    /// Store AX into a string word
+    Stosw,
+    /// Read from a port
+    In,
+    /// Write to a port
+    Out,
+    /// Software interrupt
+    Int,
+    /// Breakpoint interrupt
+    Int3,
+    /// Interrupt if overflow
+    Into,
+    /// Return from an interrupt
+    Iret,
+    /// Clear the carry flag
+    Clc,
+    /// Set the carry flag
+    Stc,
+    /// Complement the carry flag
+    Cmc,
+    /// Clear the direction flag
+    Cld,
+    /// Set the direction flag
+    Std,
+    /// Clear the interrupt flag
+    Cli,
+    /// Set the interrupt flag
+    Sti,
+    /// Halt until an interrupt
+    Hlt,
+    /// Wait for the coprocessor
+    Wait,
+    /// No operation
+    Nop,
+    /// Load AH from the flags
+    Lahf,
+    /// Store AH into the flags
+    Sahf,
+    /// Sign-extend AL into AX
+    Cbw,
+    /// Sign-extend AX into DX:AX
+    Cwd,
+    /// Translate AL through the table at BX
+    Xlatb,
+    /// Decimal adjust after addition
+    Daa,
+    /// Decimal adjust after subtraction
+    Das,
+    /// ASCII adjust after addition
+    Aaa,
+    /// ASCII adjust after subtraction
+    Aas,
+    /// ASCII adjust after multiplication
+    Aam,
+    /// ASCII adjust before division
+    Aad,
+    /// Exchange two operands
+    Xchg,
+    /// Load an effective address
+    Lea,
+    /// Load a far pointer into DS and a register
+    Lds,
+    /// Load a far pointer into ES and a register
+    Les,
+    /// Escape to a coprocessor
+    Esc,
+    /// Push all general registers (80186)
+    Pusha,
+    /// Pop all general registers (80186)
+    Popa,
+    /// Check an index against array bounds (80186)
+    Bound,
+    /// Make a stack frame (80186)
+    Enter,
+    /// Release a stack frame (80186)
+    Leave,
+    /// Read a string byte from a port (80186)
+    Insb,
+    /// Read a string word from a port (80186)
+    Insw,
+    /// Write a string byte to a port (80186)
+    Outsb,
+    /// Write a string word to a port (80186)
+    Outsw,
+    /// Store the local descriptor table register (80286)
+    Sldt,
+    /// Store the task register (80286)
+    Str,
+    /// Load the local descriptor table register (80286)
+    Lldt,
+    /// Load the task register (80286)
+    Ltr,
+    /// Verify a segment for reading (80286)
+    Verr,
+    /// Verify a segment for writing (80286)
+    Verw,
+    /// Store the global descriptor table register (80286)
+    Sgdt,
+    /// Store the interrupt descriptor table register (80286)
+    Sidt,
+    /// Load the global descriptor table register (80286)
+    Lgdt,
+    /// Load the interrupt descriptor table register (80286)
+    Lidt,
+    /// Store the machine status word (80286)
+    Smsw,
+    /// Load the machine status word (80286)
+    Lmsw,
+    /// Load access rights (80286)
+    Lar,
+    /// Load a segment limit (80286)
+    Lsl,
+    /// Clear the task-switched flag (80286)
+    Clts,
+    /// Adjust a selector's requested privilege level (80286)
+    Arpl,
+    /// Test a bit (V20)
+    Test1,
+    /// Clear a bit (V20)
+    Clr1,
+    /// Set a bit (V20)
+    Set1,
+    /// Invert a bit (V20)
+    Not1,
+    /// Add packed decimal strings (V20)
+    Add4s,
+    /// Subtract packed decimal strings (V20)
+    Sub4s,
+    /// Compare packed decimal strings (V20)
+    Cmp4s,
+    /// Rotate a nibble left through AL (V20)
+    Rol4,
+    /// Rotate a nibble right through AL (V20)
+    Ror4,
+    /// Insert a bit field (V20)
+    Ins,
+    /// Extract a bit field (V20)
+    Ext,
+    /// Break into 8080 emulation mode (V20)
+    Brkem,
+    /// Set AL from the carry flag (undocumented)
+    Salc,
+    /// Set every bit of the operand (undocumented)
+    Setmo,
+    /// Set every bit of the operand if CL is not zero (undocumented)
+    Setmoc,
+    /// Add reals (8087)
+    Fadd,
+    /// Multiply reals (8087)
+    Fmul,
+    /// Compare reals (8087)
+    Fcom,
+    /// Compare reals and pop (8087)
+    Fcomp,
+    /// Subtract reals (8087)
+    Fsub,
+    /// Subtract reals reversed (8087)
+    Fsubr,
+    /// Divide reals (8087)
+    Fdiv,
+    /// Divide reals reversed (8087)
+    Fdivr,
+    /// Add an integer (8087)
+    Fiadd,
+    /// Multiply by an integer (8087)
+    Fimul,
+    /// Compare with an integer (8087)
+    Ficom,
+    /// Compare with an integer and pop (8087)
+    Ficomp,
+    /// Subtract an integer (8087)
+    Fisub,
+    /// Subtract from an integer (8087)
+    Fisubr,
+    /// Divide by an integer (8087)
+    Fidiv,
+    /// Divide an integer (8087)
+    Fidivr,
+    /// Load a real (8087)
+    Fld,
+    /// Store a real (8087)
+    Fst,
+    /// Store a real and pop (8087)
+    Fstp,
+    /// Load the environment (8087)
+    Fldenv,
+    /// Load the control word (8087)
+    Fldcw,
+    /// Store the environment without waiting (8087)
+    Fnstenv,
+    /// Store the control word without waiting (8087)
+    Fnstcw,
+    /// Load an integer (8087)
+    Fild,
+    /// Store an integer (8087)
+    Fist,
+    /// Store an integer and pop (8087)
+    Fistp,
+    /// Restore the saved state (8087)
+    Frstor,
+    /// Save the state without waiting (8087)
+    Fnsave,
+    /// Store the status word without waiting (8087)
+    Fnstsw,
+    /// Load packed decimal (8087)
+    Fbld,
+    /// Store packed decimal and pop (8087)
+    Fbstp,
+    /// Exchange stack registers (8087)
+    Fxch,
+    /// No operation (8087)
+    Fnop,
+    /// Change sign (8087)
+    Fchs,
+    /// Absolute value (8087)
+    Fabs,
+    /// Compare with zero (8087)
+    Ftst,
+    /// Examine the top of the stack (8087)
+    Fxam,
+    /// Load +1.0 (8087)
+    Fld1,
+    /// Load log2(10) (8087)
+    Fldl2t,
+    /// Load log2(e) (8087)
+    Fldl2e,
+    /// Load pi (8087)
+    Fldpi,
+    /// Load log10(2) (8087)
+    Fldlg2,
+    /// Load ln(2) (8087)
+    Fldln2,
+    /// Load +0.0 (8087)
+    Fldz,
+    /// Compute 2^x - 1 (8087)
+    F2xm1,
+    /// Compute y * log2(x) (8087)
+    Fyl2x,
+    /// Partial tangent (8087)
+    Fptan,
+    /// Partial arctangent (8087)
+    Fpatan,
+    /// Extract exponent and significand (8087)
+    Fxtract,
+    /// Decrement the stack pointer (8087)
+    Fdecstp,
+    /// Increment the stack pointer (8087)
+    Fincstp,
+    /// Partial remainder (8087)
+    Fprem,
+    /// Compute y * log2(x + 1) (8087)
+    Fyl2xp1,
+    /// Square root (8087)
+    Fsqrt,
+    /// Round to an integer (8087)
+    Frndint,
+    /// Scale by a power of two (8087)
+    Fscale,
+    /// Enable interrupts without waiting (8087)
+    Fneni,
+    /// Disable interrupts without waiting (8087)
+    Fndisi,
+    /// Clear exceptions without waiting (8087)
+    Fnclex,
+    /// Initialize without waiting (8087)
+    Fninit,
+    /// Mark a stack register empty (8087)
+    Ffree,
+    /// Add reals and pop (8087)
+    Faddp,
+    /// Multiply reals and pop (8087)
+    Fmulp,
+    /// Compare reals and pop twice (8087)
+    Fcompp,
+    /// Subtract reals and pop (8087)
+    Fsubp,
+    /// Subtract reals reversed and pop (8087)
+    Fsubrp,
+    /// Divide reals and pop (8087)
+    Fdivp,
+    /// Divide reals reversed and pop (8087)
+    Fdivrp,
+    /// Initialize after a `wait` (8087)
+    Finit,
+    /// Clear exceptions after a `wait` (8087)
+    Fclex,
+    /// Enable interrupts after a `wait` (8087)
+    Feni,
+    /// Disable interrupts after a `wait` (8087)
+    Fdisi,
+    /// Store the status word after a `wait` (8087)
+    Fstsw,
+    /// Store the control word after a `wait` (8087)
+    Fstcw,
+    /// Store the environment after a `wait` (8087)
+    Fstenv,
+    /// Save the state after a `wait` (8087)
+    Fsave,
+}
+
+/// The layout of the operands that follow an opcode byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    /// `......dw` with a ModRM byte selecting a register and a register or memory operand
+    RegMemWithReg,
+    /// `.......w` with a ModRM byte followed by immediate data for the register or memory operand
+    ImmediateToRegMem,
+    /// `......sw` with a ModRM byte followed by immediate data, a single byte when the s bit
+    /// asks for it to be sign-extended to a word
+    SignExtendedImmediateToRegMem,
+    /// `....wreg` followed by immediate data for the register
+    ImmediateToReg,
+    /// `.......w` followed by immediate data for the accumulator
+    ImmediateToAccumulator,
+    /// `.......w` followed by a 16-bit address to load the accumulator from
+    MemoryToAccumulator,
+    /// `.......w` followed by a 16-bit address to store the accumulator to
+    AccumulatorToMemory,
+    /// `......d.` with a ModRM byte whose reg field selects a segment register
+    SegmentRegister,
+    /// An 8-bit signed displacement from the end of the instruction follows the opcode byte
+    ShortJump,
+    /// A 16-bit signed displacement from the end of the instruction follows the opcode byte
+    NearJump,
+    /// A 16-bit offset followed by a 16-bit segment follows the opcode byte
+    FarPointer,
+    /// `.......w` with a ModRM byte selecting the only, register or memory, operand
+    RegMem,
+    /// A ModRM byte selecting the memory location of a far pointer
+    FarRegMem,
+    /// `.....xxx` with a ModRM byte whose reg field completes the 6-bit opcode of a coprocessor
+    /// instruction working on the register or memory operand
+    Escape,
+    /// A ModRM byte selecting a word register and a memory operand, such as an address to load
+    LoadAddress,
+    /// A ModRM byte selecting a memory operand, such as the image of a descriptor table register
+    Memory,
+    /// A ModRM byte selecting a word register or memory operand
+    WordRegMem,
+    /// A ModRM byte selecting a destination word register and a word register or memory source
+    WordRegWithRegMem,
+    /// A ModRM byte selecting a destination word register or memory operand and a word register
+    WordRegMemWithReg,
+    /// `......s.` with a ModRM byte selecting a word register and a register or memory operand,
+    /// followed by immediate data that is a single sign-extended byte when the s bit is set
+    RegMemWithImmediate,
+    /// 16-bit immediate data follows the opcode byte
+    Immediate16,
+    /// `.....reg` with a word register in the opcode byte
+    Register,
+    /// `...sr...` with a segment register in the opcode byte
+    Segment,
+    /// `.....reg` with a word register in the opcode byte to exchange with the accumulator
+    RegisterWithAccumulator,
+    /// `......vw` with a ModRM byte selecting the operand to shift by 1 or, with the v bit set, by cl
+    Shift,
+    /// `.......w` with a ModRM byte selecting the operand to shift, followed by an 8-bit count
+    ShiftImmediate,
+    /// `.......w` with a ModRM byte selecting the operand whose bit cl numbers
+    BitCl,
+    /// `.......w` with a ModRM byte selecting the operand whose bit the following byte numbers
+    BitImmediate,
+    /// A ModRM byte selecting two byte registers, the bit field offset and length
+    ByteRegisters,
+    /// A ModRM byte selecting the byte register holding the bit field offset, followed by the
+    /// bit field length
+    ByteRegisterWithImmediate,
+    /// `.......w` followed by an 8-bit port number to transfer the accumulator through
+    FixedPort,
+    /// `.......w` transferring the accumulator through the port in dx
+    VariablePort,
+    /// 8-bit immediate data follows the opcode byte
+    Immediate8,
+    /// `......s.` followed by 16-bit immediate data, or a single byte sign-extended to a word
+    /// when the s bit is set
+    SignExtendedImmediate,
+    /// A 16-bit frame size followed by an 8-bit nesting level follows the opcode byte
+    StackFrame,
+    /// The number base of a decimal adjustment follows the opcode byte, 10 unless stated
+    Base,
+    /// No operands follow the opcode byte
+    Implied,
 }
 
-impl X86Opcode {
-    pub(crate) fn from_u8(value: u8) -> Self {
+/// The opcode byte the 80286 and the NEC V20 use to introduce a second opcode byte
+pub(crate) const TWO_BYTE_ESCAPE: u8 = 0x0F;
+
+/// Opcodes that make up a whole instruction on their own, without any operand bytes
+const SINGLE_BYTE_OPCODES: [(u8, Mnemonic); 36] = [
+    (0x27, Mnemonic::Daa),
+    (0x2F, Mnemonic::Das),
+    (0x37, Mnemonic::Aaa),
+    (0x3F, Mnemonic::Aas),
+    // 0x90 is the encoding of `xchg ax, ax`, which does nothing
+    (0x90, Mnemonic::Nop),
+    (0x98, Mnemonic::Cbw),
+    (0x99, Mnemonic::Cwd),
+    (0x9B, Mnemonic::Wait),
+    (0x9C, Mnemonic::Pushf),
+    (0x9D, Mnemonic::Popf),
+    (0x9E, Mnemonic::Sahf),
+    (0x9F, Mnemonic::Lahf),
+    (0xA4, Mnemonic::Movsb),
+    (0xA5, Mnemonic::Movsw),
+    (0xA6, Mnemonic::Cmpsb),
+    (0xA7, Mnemonic::Cmpsw),
+    (0xAA, Mnemonic::Stosb),
+    (0xAB, Mnemonic::Stosw),
+    (0xAC, Mnemonic::Lodsb),
+    (0xAD, Mnemonic::Lodsw),
+    (0xAE, Mnemonic::Scasb),
+    (0xAF, Mnemonic::Scasw),
+    (0xC3, Mnemonic::Ret),
+    (0xCB, Mnemonic::Retf),
+    (0xCC, Mnemonic::Int3),
+    (0xCE, Mnemonic::Into),
+    (0xCF, Mnemonic::Iret),
+    (0xD7, Mnemonic::Xlatb),
+    (0xF4, Mnemonic::Hlt),
+    (0xF5, Mnemonic::Cmc),
+    (0xF8, Mnemonic::Clc),
+    (0xF9, Mnemonic::Stc),
+    (0xFA, Mnemonic::Cli),
+    (0xFB, Mnemonic::Sti),
+    (0xFC, Mnemonic::Cld),
+    (0xFD, Mnemonic::Std),
+];
+
+impl Mnemonic {
+    /// Look up the opcode and operand encoding of an opcode byte in the instruction set of `cpu`.
+    /// Opcodes a later processor adds take precedence over what the byte means on the 8086.
+    pub(crate) fn decode_for_cpu(value: u8, reg: u8, cpu: Cpu) -> Option<(Self, Encoding)> {
+        let extension = match cpu {
+            Cpu::Intel8086 => None,
+            Cpu::Intel8086Silicon => Self::decode_undocumented(value, reg),
+            Cpu::Intel80186 => Self::decode_80186(value, reg),
+            Cpu::Intel80286 => Self::decode_80286(value).or_else(|| Self::decode_80186(value, reg)),
+            Cpu::NecV20 => Self::decode_80186(value, reg),
+        };
+        extension.or_else(|| Self::decode(value, reg))
+    }
+
+    /// Look up the second opcode byte following `0F` in the instruction set of `cpu`
+    pub(crate) fn decode_two_byte_for_cpu(
+        value: u8,
+        reg: u8,
+        cpu: Cpu,
+    ) -> Option<(Self, Encoding)> {
+        match cpu {
+            Cpu::Intel80286 => Self::decode_80286_two_byte(value, reg),
+            Cpu::NecV20 => Self::decode_v20_two_byte(value, reg),
+            _ => None,
+        }
+    }
+
+    /// Look up the one-byte opcodes the 80286 adds to the 80186 instruction set
+    fn decode_80286(value: u8) -> Option<(Self, Encoding)> {
         match value {
-            0b100010 => X86Opcode::Mov,
-            _ => X86Opcode::InvalidInstruction,
+            0x63 => Some((Mnemonic::Arpl, Encoding::WordRegMemWithReg)),
+            _ => None,
+        }
+    }
+
+    /// Look up the second opcode byte of the NEC V20 two-byte opcodes
+    fn decode_v20_two_byte(value: u8, reg: u8) -> Option<(Self, Encoding)> {
+        // The bit operations are ordered test, clear, set and complement in bits 2-1
+        let bit_operation = [
+            Mnemonic::Test1,
+            Mnemonic::Clr1,
+            Mnemonic::Set1,
+            Mnemonic::Not1,
+        ][(value as usize >> 1) & 0b11];
+        Some(match (value, reg) {
+            (0x10..=0x17, _) => (bit_operation, Encoding::BitCl),
+            (0x18..=0x1F, _) => (bit_operation, Encoding::BitImmediate),
+            (0x20, _) => (Mnemonic::Add4s, Encoding::Implied),
+            (0x22, _) => (Mnemonic::Sub4s, Encoding::Implied),
+            (0x26, _) => (Mnemonic::Cmp4s, Encoding::Implied),
+            (0x28, 0b000) => (Mnemonic::Rol4, Encoding::RegMem),
+            (0x2A, 0b000) => (Mnemonic::Ror4, Encoding::RegMem),
+            (0x31, _) => (Mnemonic::Ins, Encoding::ByteRegisters),
+            (0x33, _) => (Mnemonic::Ext, Encoding::ByteRegisters),
+            (0x39, 0b000) => (Mnemonic::Ins, Encoding::ByteRegisterWithImmediate),
+            (0x3B, 0b000) => (Mnemonic::Ext, Encoding::ByteRegisterWithImmediate),
+            (0xFF, _) => (Mnemonic::Brkem, Encoding::Immediate8),
+            _ => return None,
+        })
+    }
+
+    /// Look up the second opcode byte of the 80286 two-byte opcodes
+    fn decode_80286_two_byte(value: u8, reg: u8) -> Option<(Self, Encoding)> {
+        Some(match (value, reg) {
+            (0x00, 0b000) => (Mnemonic::Sldt, Encoding::WordRegMem),
+            (0x00, 0b001) => (Mnemonic::Str, Encoding::WordRegMem),
+            (0x00, 0b010) => (Mnemonic::Lldt, Encoding::WordRegMem),
+            (0x00, 0b011) => (Mnemonic::Ltr, Encoding::WordRegMem),
+            (0x00, 0b100) => (Mnemonic::Verr, Encoding::WordRegMem),
+            (0x00, 0b101) => (Mnemonic::Verw, Encoding::WordRegMem),
+            (0x01, 0b000) => (Mnemonic::Sgdt, Encoding::Memory),
+            (0x01, 0b001) => (Mnemonic::Sidt, Encoding::Memory),
+            (0x01, 0b010) => (Mnemonic::Lgdt, Encoding::Memory),
+            (0x01, 0b011) => (Mnemonic::Lidt, Encoding::Memory),
+            (0x01, 0b100) => (Mnemonic::Smsw, Encoding::WordRegMem),
+            (0x01, 0b110) => (Mnemonic::Lmsw, Encoding::WordRegMem),
+            (0x02, _) => (Mnemonic::Lar, Encoding::WordRegWithRegMem),
+            (0x03, _) => (Mnemonic::Lsl, Encoding::WordRegWithRegMem),
+            (0x06, _) => (Mnemonic::Clts, Encoding::Implied),
+            _ => return None,
+        })
+    }
+
+    /// Look up the opcodes the 8086 executes although they are not documented, which are mostly
+    /// aliases of documented opcodes that differ in a bit the processor ignores
+    pub(crate) fn decode_undocumented(value: u8, reg: u8) -> Option<(Self, Encoding)> {
+        Some(match (value, reg) {
+            (0x0F, _) => (Mnemonic::Pop, Encoding::Segment),
+            (0x60..=0x6F, _) => (Self::condition(value), Encoding::ShortJump),
+            (0x82, reg) => (
+                Self::arithmetic_logic(reg),
+                Encoding::SignExtendedImmediateToRegMem,
+            ),
+            (0xC0, _) => (Mnemonic::Ret, Encoding::Immediate16),
+            (0xC1, _) => (Mnemonic::Ret, Encoding::Implied),
+            (0xC8, _) => (Mnemonic::Retf, Encoding::Immediate16),
+            (0xC9, _) => (Mnemonic::Retf, Encoding::Implied),
+            (0xD0 | 0xD1, 0b110) => (Mnemonic::Setmo, Encoding::RegMem),
+            (0xD2 | 0xD3, 0b110) => (Mnemonic::Setmoc, Encoding::BitCl),
+            (0xD6, _) => (Mnemonic::Salc, Encoding::Implied),
+            _ => return None,
+        })
+    }
+
+    /// Look up the opcodes the 80186 adds to the 8086 instruction set
+    fn decode_80186(value: u8, reg: u8) -> Option<(Self, Encoding)> {
+        Some(match (value, reg) {
+            (0x60, _) => (Mnemonic::Pusha, Encoding::Implied),
+            (0x61, _) => (Mnemonic::Popa, Encoding::Implied),
+            (0x62, _) => (Mnemonic::Bound, Encoding::LoadAddress),
+            (0x68 | 0x6A, _) => (Mnemonic::Push, Encoding::SignExtendedImmediate),
+            (0x69 | 0x6B, _) => (Mnemonic::Imul, Encoding::RegMemWithImmediate),
+            (0x6C, _) => (Mnemonic::Insb, Encoding::Implied),
+            (0x6D, _) => (Mnemonic::Insw, Encoding::Implied),
+            (0x6E, _) => (Mnemonic::Outsb, Encoding::Implied),
+            (0x6F, _) => (Mnemonic::Outsw, Encoding::Implied),
+            (0xC0..=0xC1, reg) => (Self::shift(reg), Encoding::ShiftImmediate),
+            (0xD0..=0xD3, 0b110) => (Mnemonic::Sal, Encoding::Shift),
+            (0xC8, _) => (Mnemonic::Enter, Encoding::StackFrame),
+            (0xC9, _) => (Mnemonic::Leave, Encoding::Implied),
+            _ => return None,
+        })
+    }
+
+    /// Look up the opcode and operand encoding of an opcode byte on the 8086.
+    /// Some opcodes also require the reg field of the following ModRM byte to be a fixed value.
+    pub(crate) fn decode(value: u8, reg: u8) -> Option<(Self, Encoding)> {
+        Some(match (value, reg) {
+            (0x06 | 0x0E | 0x16 | 0x1E, _) => (Mnemonic::Push, Encoding::Segment),
+            (0x07 | 0x17 | 0x1F, _) => (Mnemonic::Pop, Encoding::Segment),
+            (0x00..=0x3F, _) if value & 0b11000100 == 0b00000000 => {
+                (Self::arithmetic_logic(value >> 3), Encoding::RegMemWithReg)
+            }
+            (0x00..=0x3F, _) if value & 0b11000110 == 0b00000100 => (
+                Self::arithmetic_logic(value >> 3),
+                Encoding::ImmediateToAccumulator,
+            ),
+            (0x40..=0x47, _) => (Mnemonic::Inc, Encoding::Register),
+            (0x48..=0x4F, _) => (Mnemonic::Dec, Encoding::Register),
+            (0x50..=0x57, _) => (Mnemonic::Push, Encoding::Register),
+            (0x58..=0x5F, _) => (Mnemonic::Pop, Encoding::Register),
+            (0x70..=0x7F, _) => (Self::condition(value), Encoding::ShortJump),
+            (0x80 | 0x81 | 0x83, reg) => (
+                Self::arithmetic_logic(reg),
+                Encoding::SignExtendedImmediateToRegMem,
+            ),
+            (0x84..=0x85, _) => (Mnemonic::Test, Encoding::RegMemWithReg),
+            (0x86..=0x87, _) => (Mnemonic::Xchg, Encoding::RegMemWithReg),
+            (0x88..=0x8B, _) => (Mnemonic::Mov, Encoding::RegMemWithReg),
+            (0x8C | 0x8E, 0b000..=0b011) => (Mnemonic::Mov, Encoding::SegmentRegister),
+            (0x8D, _) => (Mnemonic::Lea, Encoding::LoadAddress),
+            (0x8F, 0b000) => (Mnemonic::Pop, Encoding::RegMem),
+            (0x91..=0x97, _) => (Mnemonic::Xchg, Encoding::RegisterWithAccumulator),
+            (0x9A, _) => (Mnemonic::Call, Encoding::FarPointer),
+            (0xA0..=0xA1, _) => (Mnemonic::Mov, Encoding::MemoryToAccumulator),
+            (0xA2..=0xA3, _) => (Mnemonic::Mov, Encoding::AccumulatorToMemory),
+            (0xA8..=0xA9, _) => (Mnemonic::Test, Encoding::ImmediateToAccumulator),
+            (0xB0..=0xBF, _) => (Mnemonic::Mov, Encoding::ImmediateToReg),
+            (0xC2, _) => (Mnemonic::Ret, Encoding::Immediate16),
+            (0xC4, _) => (Mnemonic::Les, Encoding::LoadAddress),
+            (0xC5, _) => (Mnemonic::Lds, Encoding::LoadAddress),
+            (0xC6..=0xC7, 0b000) => (Mnemonic::Mov, Encoding::ImmediateToRegMem),
+            (0xCA, _) => (Mnemonic::Retf, Encoding::Immediate16),
+            (0xCD, _) => (Mnemonic::Int, Encoding::Immediate8),
+            // The 8086 runs reg=110 as the undocumented SETMO, not as a shift
+            (0xD0..=0xD3, 0b110) => return Self::decode_undocumented(value, reg),
+            (0xD0..=0xD3, reg) => (Self::shift(reg), Encoding::Shift),
+            (0xD4, _) => (Mnemonic::Aam, Encoding::Base),
+            (0xD5, _) => (Mnemonic::Aad, Encoding::Base),
+            (0xD8..=0xDF, _) => (Mnemonic::Esc, Encoding::Escape),
+            (0xE0, _) => (Mnemonic::Loopnz, Encoding::ShortJump),
+            (0xE1, _) => (Mnemonic::Loopz, Encoding::ShortJump),
+            (0xE2, _) => (Mnemonic::Loop, Encoding::ShortJump),
+            (0xE3, _) => (Mnemonic::Jcxz, Encoding::ShortJump),
+            (0xE4..=0xE5, _) => (Mnemonic::In, Encoding::FixedPort),
+            (0xE6..=0xE7, _) => (Mnemonic::Out, Encoding::FixedPort),
+            (0xE8, _) => (Mnemonic::Call, Encoding::NearJump),
+            (0xE9, _) => (Mnemonic::Jmp, Encoding::NearJump),
+            (0xEA, _) => (Mnemonic::Jmp, Encoding::FarPointer),
+            (0xEB, _) => (Mnemonic::Jmp, Encoding::ShortJump),
+            (0xEC..=0xED, _) => (Mnemonic::In, Encoding::VariablePort),
+            (0xEE..=0xEF, _) => (Mnemonic::Out, Encoding::VariablePort),
+            (0xF6..=0xF7, 0b000) => (Mnemonic::Test, Encoding::ImmediateToRegMem),
+            (0xF6..=0xF7, 0b010..=0b111) => (Self::unary(reg), Encoding::RegMem),
+            (0xFE..=0xFF, 0b000) => (Mnemonic::Inc, Encoding::RegMem),
+            (0xFE..=0xFF, 0b001) => (Mnemonic::Dec, Encoding::RegMem),
+            (0xFF, 0b010) => (Mnemonic::Call, Encoding::RegMem),
+            (0xFF, 0b011) => (Mnemonic::Call, Encoding::FarRegMem),
+            (0xFF, 0b100) => (Mnemonic::Jmp, Encoding::RegMem),
+            (0xFF, 0b101) => (Mnemonic::Jmp, Encoding::FarRegMem),
+            (0xFF, 0b110) => (Mnemonic::Push, Encoding::RegMem),
+            _ => (Self::single_byte(value)?, Encoding::Implied),
+        })
+    }
+
+    /// Look up an opcode that needs no operand bytes
+    fn single_byte(value: u8) -> Option<Self> {
+        SINGLE_BYTE_OPCODES
+            .iter()
+            .find(|(byte, _)| *byte == value)
+            .map(|(_, opcode)| *opcode)
+    }
+
+    /// Check whether the opcode is a string instruction a repeat prefix applies to
+    pub(crate) fn is_string(&self) -> bool {
+        matches!(
+            self,
+            Mnemonic::Movsb
+                | Mnemonic::Movsw
+                | Mnemonic::Cmpsb
+                | Mnemonic::Cmpsw
+                | Mnemonic::Scasb
+                | Mnemonic::Scasw
+                | Mnemonic::Lodsb
+                | Mnemonic::Lodsw
+                | Mnemonic::Stosb
+                | Mnemonic::Stosw
+                | Mnemonic::Insb
+                | Mnemonic::Insw
+                | Mnemonic::Outsb
+                | Mnemonic::Outsw
+        )
+    }
+
+    /// Check whether the opcode addresses memory through DS without a ModRM byte, so that a
+    /// segment override prefix applies to it. The ES:DI destination of `stos`, `scas` and `ins`
+    /// cannot be overridden.
+    pub(crate) fn uses_implied_memory(&self) -> bool {
+        matches!(
+            self,
+            Mnemonic::Movsb
+                | Mnemonic::Movsw
+                | Mnemonic::Cmpsb
+                | Mnemonic::Cmpsw
+                | Mnemonic::Lodsb
+                | Mnemonic::Lodsw
+                | Mnemonic::Outsb
+                | Mnemonic::Outsw
+                | Mnemonic::Xlatb
+        )
+    }
+
+    /// Check whether the opcode is a string comparison, which repeats while equal or not equal
+    pub(crate) fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Mnemonic::Cmpsb | Mnemonic::Cmpsw | Mnemonic::Scasb | Mnemonic::Scasw
+        )
+    }
+
+    /// Look up the arithmetic or logical operation selected by bits 5-3 of the opcode byte or,
+    /// for the immediate group, by the reg field
+    fn arithmetic_logic(field: u8) -> Self {
+        match field & 0b111 {
+            0b000 => Mnemonic::Add,
+            0b001 => Mnemonic::Or,
+            0b010 => Mnemonic::Adc,
+            0b011 => Mnemonic::Sbb,
+            0b100 => Mnemonic::And,
+            0b101 => Mnemonic::Sub,
+            0b110 => Mnemonic::Xor,
+            _ => Mnemonic::Cmp,
+        }
+    }
+
+    /// Look up the unary operation selected by the reg field of the `1111011w` group
+    fn unary(field: u8) -> Self {
+        match field & 0b111 {
+            0b010 => Mnemonic::Not,
+            0b011 => Mnemonic::Neg,
+            0b100 => Mnemonic::Mul,
+            0b101 => Mnemonic::Imul,
+            0b110 => Mnemonic::Div,
+            _ => Mnemonic::Idiv,
+        }
+    }
+
+    /// Look up the shift or rotation selected by the reg field of the `110100vw` group.
+    /// The undocumented reg=110 encoding decodes as `sal`, the alias the 80186 and later execute
+    /// it as; the 8086 itself runs it as SETMO, setting the operand to all ones.
+    fn shift(field: u8) -> Self {
+        match field & 0b111 {
+            0b000 => Mnemonic::Rol,
+            0b001 => Mnemonic::Ror,
+            0b010 => Mnemonic::Rcl,
+            0b011 => Mnemonic::Rcr,
+            0b100 => Mnemonic::Shl,
+            0b101 => Mnemonic::Shr,
+            0b110 => Mnemonic::Sal,
+            _ => Mnemonic::Sar,
+        }
+    }
+
+    /// Look up the conditional jump selected by the low four bits of the opcode byte
+    fn condition(field: u8) -> Self {
+        match field & 0b1111 {
+            0b0000 => Mnemonic::Jo,
+            0b0001 => Mnemonic::Jno,
+            0b0010 => Mnemonic::Jb,
+            0b0011 => Mnemonic::Jnb,
+            0b0100 => Mnemonic::Jz,
+            0b0101 => Mnemonic::Jnz,
+            0b0110 => Mnemonic::Jbe,
+            0b0111 => Mnemonic::Ja,
+            0b1000 => Mnemonic::Js,
+            0b1001 => Mnemonic::Jns,
+            0b1010 => Mnemonic::Jp,
+            0b1011 => Mnemonic::Jnp,
+            0b1100 => Mnemonic::Jl,
+            0b1101 => Mnemonic::Jnl,
+            0b1110 => Mnemonic::Jle,
+            _ => Mnemonic::Jg,
         }
     }
 }
 
-impl fmt::Display for X86Opcode {
+impl fmt::Display for Mnemonic {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let value = match self {
-            X86Opcode::Mov => "mov",
-            X86Opcode::InvalidInstruction => "invalid instruction",
+            Mnemonic::Mov => "mov",
+            Mnemonic::Add => "add",
+            Mnemonic::Adc => "adc",
+            Mnemonic::Sub => "sub",
+            Mnemonic::Sbb => "sbb",
+            Mnemonic::Cmp => "cmp",
+            Mnemonic::And => "and",
+            Mnemonic::Or => "or",
+            Mnemonic::Xor => "xor",
+            Mnemonic::Test => "test",
+            Mnemonic::Jo => "jo",
+            Mnemonic::Jno => "jno",
+            Mnemonic::Jb => "jb",
+            Mnemonic::Jnb => "jnb",
+            Mnemonic::Jz => "jz",
+            Mnemonic::Jnz => "jnz",
+            Mnemonic::Jbe => "jbe",
+            Mnemonic::Ja => "ja",
+            Mnemonic::Js => "js",
+            Mnemonic::Jns => "jns",
+            Mnemonic::Jp => "jp",
+            Mnemonic::Jnp => "jnp",
+            Mnemonic::Jl => "jl",
+            Mnemonic::Jnl => "jnl",
+            Mnemonic::Jle => "jle",
+            Mnemonic::Jg => "jg",
+            Mnemonic::Loopnz => "loopnz",
+            Mnemonic::Loopz => "loopz",
+            Mnemonic::Loop => "loop",
+            Mnemonic::Jcxz => "jcxz",
+            Mnemonic::Call => "call",
+            Mnemonic::Jmp => "jmp",
+            Mnemonic::Ret => "ret",
+            Mnemonic::Retf => "retf",
+            Mnemonic::Push => "push",
+            Mnemonic::Pop => "pop",
+            Mnemonic::Pushf => "pushf",
+            Mnemonic::Popf => "popf",
+            Mnemonic::Inc => "inc",
+            Mnemonic::Dec => "dec",
+            Mnemonic::Neg => "neg",
+            Mnemonic::Not => "not",
+            Mnemonic::Mul => "mul",
+            Mnemonic::Imul => "imul",
+            Mnemonic::Div => "div",
+            Mnemonic::Idiv => "idiv",
+            Mnemonic::Rol => "rol",
+            Mnemonic::Ror => "ror",
+            Mnemonic::Rcl => "rcl",
+            Mnemonic::Rcr => "rcr",
+            Mnemonic::Shl => "shl",
+            Mnemonic::Shr => "shr",
+            Mnemonic::Sal => "sal",
+            Mnemonic::Sar => "sar",
+            Mnemonic::Movsb => "movsb",
+            Mnemonic::Movsw => "movsw",
+            Mnemonic::Cmpsb => "cmpsb",
+            Mnemonic::Cmpsw => "cmpsw",
+            Mnemonic::Scasb => "scasb",
+            Mnemonic::Scasw => "scasw",
+            Mnemonic::Lodsb => "lodsb",
+            Mnemonic::Lodsw => "lodsw",
+            Mnemonic::Stosb => "stosb",
+            Mnemonic::Stosw => "stosw",
+            Mnemonic::In => "in",
+            Mnemonic::Out => "out",
+            Mnemonic::Int => "int",
+            Mnemonic::Int3 => "int3",
+            Mnemonic::Into => "into",
+            Mnemonic::Iret => "iret",
+            Mnemonic::Clc => "clc",
+            Mnemonic::Stc => "stc",
+            Mnemonic::Cmc => "cmc",
+            Mnemonic::Cld => "cld",
+            Mnemonic::Std => "std",
+            Mnemonic::Cli => "cli",
+            Mnemonic::Sti => "sti",
+            Mnemonic::Hlt => "hlt",
+            Mnemonic::Wait => "wait",
+            Mnemonic::Nop => "nop",
+            Mnemonic::Lahf => "lahf",
+            Mnemonic::Sahf => "sahf",
+            Mnemonic::Cbw => "cbw",
+            Mnemonic::Cwd => "cwd",
+            Mnemonic::Xlatb => "xlatb",
+            Mnemonic::Daa => "daa",
+            Mnemonic::Das => "das",
+            Mnemonic::Aaa => "aaa",
+            Mnemonic::Aas => "aas",
+            Mnemonic::Aam => "aam",
+            Mnemonic::Aad => "aad",
+            Mnemonic::Xchg => "xchg",
+            Mnemonic::Lea => "lea",
+            Mnemonic::Lds => "lds",
+            Mnemonic::Les => "les",
+            Mnemonic::Esc => "esc",
+            Mnemonic::Pusha => "pusha",
+            Mnemonic::Popa => "popa",
+            Mnemonic::Bound => "bound",
+            Mnemonic::Enter => "enter",
+            Mnemonic::Leave => "leave",
+            Mnemonic::Insb => "insb",
+            Mnemonic::Insw => "insw",
+            Mnemonic::Outsb => "outsb",
+            Mnemonic::Outsw => "outsw",
+            Mnemonic::Sldt => "sldt",
+            Mnemonic::Str => "str",
+            Mnemonic::Lldt => "lldt",
+            Mnemonic::Ltr => "ltr",
+            Mnemonic::Verr => "verr",
+            Mnemonic::Verw => "verw",
+            Mnemonic::Sgdt => "sgdt",
+            Mnemonic::Sidt => "sidt",
+            Mnemonic::Lgdt => "lgdt",
+            Mnemonic::Lidt => "lidt",
+            Mnemonic::Smsw => "smsw",
+            Mnemonic::Lmsw => "lmsw",
+            Mnemonic::Lar => "lar",
+            Mnemonic::Lsl => "lsl",
+            Mnemonic::Clts => "clts",
+            Mnemonic::Arpl => "arpl",
+            Mnemonic::Test1 => "test1",
+            Mnemonic::Clr1 => "clr1",
+            Mnemonic::Set1 => "set1",
+            Mnemonic::Not1 => "not1",
+            Mnemonic::Add4s => "add4s",
+            Mnemonic::Sub4s => "sub4s",
+            Mnemonic::Cmp4s => "cmp4s",
+            Mnemonic::Rol4 => "rol4",
+            Mnemonic::Ror4 => "ror4",
+            Mnemonic::Ins => "ins",
+            Mnemonic::Ext => "ext",
+            Mnemonic::Brkem => "brkem",
+            Mnemonic::Salc => "salc",
+            Mnemonic::Setmo => "setmo",
+            Mnemonic::Setmoc => "setmoc",
+            Mnemonic::Fadd => "fadd",
+            Mnemonic::Fmul => "fmul",
+            Mnemonic::Fcom => "fcom",
+            Mnemonic::Fcomp => "fcomp",
+            Mnemonic::Fsub => "fsub",
+            Mnemonic::Fsubr => "fsubr",
+            Mnemonic::Fdiv => "fdiv",
+            Mnemonic::Fdivr => "fdivr",
+            Mnemonic::Fiadd => "fiadd",
+            Mnemonic::Fimul => "fimul",
+            Mnemonic::Ficom => "ficom",
+            Mnemonic::Ficomp => "ficomp",
+            Mnemonic::Fisub => "fisub",
+            Mnemonic::Fisubr => "fisubr",
+            Mnemonic::Fidiv => "fidiv",
+            Mnemonic::Fidivr => "fidivr",
+            Mnemonic::Fld => "fld",
+            Mnemonic::Fst => "fst",
+            Mnemonic::Fstp => "fstp",
+            Mnemonic::Fldenv => "fldenv",
+            Mnemonic::Fldcw => "fldcw",
+            Mnemonic::Fnstenv => "fnstenv",
+            Mnemonic::Fnstcw => "fnstcw",
+            Mnemonic::Fild => "fild",
+            Mnemonic::Fist => "fist",
+            Mnemonic::Fistp => "fistp",
+            Mnemonic::Frstor => "frstor",
+            Mnemonic::Fnsave => "fnsave",
+            Mnemonic::Fnstsw => "fnstsw",
+            Mnemonic::Fbld => "fbld",
+            Mnemonic::Fbstp => "fbstp",
+            Mnemonic::Fxch => "fxch",
+            Mnemonic::Fnop => "fnop",
+            Mnemonic::Fchs => "fchs",
+            Mnemonic::Fabs => "fabs",
+            Mnemonic::Ftst => "ftst",
+            Mnemonic::Fxam => "fxam",
+            Mnemonic::Fld1 => "fld1",
+            Mnemonic::Fldl2t => "fldl2t",
+            Mnemonic::Fldl2e => "fldl2e",
+            Mnemonic::Fldpi => "fldpi",
+            Mnemonic::Fldlg2 => "fldlg2",
+            Mnemonic::Fldln2 => "fldln2",
+            Mnemonic::Fldz => "fldz",
+            Mnemonic::F2xm1 => "f2xm1",
+            Mnemonic::Fyl2x => "fyl2x",
+            Mnemonic::Fptan => "fptan",
+            Mnemonic::Fpatan => "fpatan",
+            Mnemonic::Fxtract => "fxtract",
+            Mnemonic::Fdecstp => "fdecstp",
+            Mnemonic::Fincstp => "fincstp",
+            Mnemonic::Fprem => "fprem",
+            Mnemonic::Fyl2xp1 => "fyl2xp1",
+            Mnemonic::Fsqrt => "fsqrt",
+            Mnemonic::Frndint => "frndint",
+            Mnemonic::Fscale => "fscale",
+            Mnemonic::Fneni => "fneni",
+            Mnemonic::Fndisi => "fndisi",
+            Mnemonic::Fnclex => "fnclex",
+            Mnemonic::Fninit => "fninit",
+            Mnemonic::Ffree => "ffree",
+            Mnemonic::Faddp => "faddp",
+            Mnemonic::Fmulp => "fmulp",
+            Mnemonic::Fcompp => "fcompp",
+            Mnemonic::Fsubp => "fsubp",
+            Mnemonic::Fsubrp => "fsubrp",
+            Mnemonic::Fdivp => "fdivp",
+            Mnemonic::Fdivrp => "fdivrp",
+            Mnemonic::Finit => "finit",
+            Mnemonic::Fclex => "fclex",
+            Mnemonic::Feni => "feni",
+            Mnemonic::Fdisi => "fdisi",
+            Mnemonic::Fstsw => "fstsw",
+            Mnemonic::Fstcw => "fstcw",
+            Mnemonic::Fstenv => "fstenv",
+            Mnemonic::Fsave => "fsave",
         };
         write!(f, "{}", value)
     }
@@ -31,43 +1101,618 @@ mod test {
     use super::*;
 
     #[test]
-    fn test_from_u8() {
-        assert_eq!(X86Opcode::from_u8(0b100010), X86Opcode::Mov);
-        assert_eq!(X86Opcode::from_u8(0b100011), X86Opcode::InvalidInstruction);
+    fn test_decode_opcode() {
+        assert_eq!(
+            Mnemonic::decode(0b10001000, 0b000).unwrap().0,
+            Mnemonic::Mov
+        );
+        assert_eq!(Mnemonic::decode(0b11111111, 0b111), None);
     }
 
     #[test]
     fn test_fmt() {
-        assert_eq!(format!("{}", X86Opcode::Mov), "mov");
-        assert_eq!(
-            format!("{}", X86Opcode::InvalidInstruction),
-            "invalid instruction"
-        );
+        assert_eq!(format!("{}", Mnemonic::Mov), "mov");
+        assert_eq!(format!("{}", Mnemonic::Add), "add");
+        assert_eq!(format!("{}", Mnemonic::Adc), "adc");
+        assert_eq!(format!("{}", Mnemonic::Sub), "sub");
+        assert_eq!(format!("{}", Mnemonic::Sbb), "sbb");
+        assert_eq!(format!("{}", Mnemonic::Cmp), "cmp");
+        assert_eq!(format!("{}", Mnemonic::And), "and");
+        assert_eq!(format!("{}", Mnemonic::Or), "or");
+        assert_eq!(format!("{}", Mnemonic::Xor), "xor");
+        assert_eq!(format!("{}", Mnemonic::Test), "test");
     }
 
     #[test]
     fn test_debug_trait() {
         // Create instances
-        let mov = X86Opcode::Mov;
-        let invalid_instruction = X86Opcode::InvalidInstruction;
+        let mov = Mnemonic::Mov;
+        let fninit = Mnemonic::Fninit;
 
         // Format instances using debug formatting
         let mov_debug_string = format!("{:?}", mov);
-        let invalid_instruction_debug_string = format!("{:?}", invalid_instruction);
+        let fninit_debug_string = format!("{:?}", fninit);
 
         // Assertions
         assert_eq!(mov_debug_string, "Mov"); // Compare against expected output
-        assert_eq!(invalid_instruction_debug_string, "InvalidInstruction"); // Compare against expected output
+        assert_eq!(fninit_debug_string, "Fninit"); // Compare against expected output
     }
 
     #[test]
-    fn test_opcode_from_u8() {
-        // Test known opcode 100010 in binary
-        let opcode = X86Opcode::from_u8(0b100010);
-        assert_eq!(opcode, X86Opcode::Mov);
+    fn test_decode_mov_reg_mem_opcode() {
+        // Test all four d and w combinations of 100010dw
+        for value in 0b10001000..=0b10001011 {
+            assert_eq!(Mnemonic::decode(value, 0b000).unwrap().0, Mnemonic::Mov);
+        }
 
         // Test unknown opcode
-        let invalid_opcode = X86Opcode::from_u8(0b111111);
-        assert_eq!(invalid_opcode, X86Opcode::InvalidInstruction);
+        let invalid_opcode = Mnemonic::decode(0b11111111, 0b111);
+        assert_eq!(invalid_opcode, None);
+    }
+
+    #[test]
+    fn test_decode_mov_encodings() {
+        assert_eq!(
+            Mnemonic::decode(0b10001011, 0b101),
+            Some((Mnemonic::Mov, Encoding::RegMemWithReg))
+        );
+        for value in 0b10110000..=0b10111111 {
+            assert_eq!(
+                Mnemonic::decode(value, 0b000),
+                Some((Mnemonic::Mov, Encoding::ImmediateToReg))
+            );
+        }
+        assert_eq!(
+            Mnemonic::decode(0b11000110, 0b000),
+            Some((Mnemonic::Mov, Encoding::ImmediateToRegMem))
+        );
+        assert_eq!(
+            Mnemonic::decode(0b11000111, 0b000),
+            Some((Mnemonic::Mov, Encoding::ImmediateToRegMem))
+        );
+    }
+
+    #[test]
+    fn test_decode_accumulator_and_segment_encodings() {
+        assert_eq!(
+            Mnemonic::decode(0b10100001, 0b000),
+            Some((Mnemonic::Mov, Encoding::MemoryToAccumulator))
+        );
+        assert_eq!(
+            Mnemonic::decode(0b10100010, 0b000),
+            Some((Mnemonic::Mov, Encoding::AccumulatorToMemory))
+        );
+        for sr in 0b000..=0b011 {
+            assert_eq!(
+                Mnemonic::decode(0b10001110, sr),
+                Some((Mnemonic::Mov, Encoding::SegmentRegister))
+            );
+            assert_eq!(
+                Mnemonic::decode(0b10001100, sr),
+                Some((Mnemonic::Mov, Encoding::SegmentRegister))
+            );
+        }
+        assert_eq!(Mnemonic::decode(0b10001110, 0b100), None);
+    }
+
+    #[test]
+    fn test_decode_immediate_to_reg_mem_requires_reg_000() {
+        for reg in 0b001..=0b111 {
+            assert_eq!(Mnemonic::decode(0b11000111, reg), None);
+        }
+    }
+
+    #[test]
+    fn test_decode_arithmetic_encodings() {
+        let operations = [
+            (0b000, Mnemonic::Add),
+            (0b010, Mnemonic::Adc),
+            (0b011, Mnemonic::Sbb),
+            (0b101, Mnemonic::Sub),
+            (0b111, Mnemonic::Cmp),
+        ];
+        for (field, opcode) in operations {
+            for dw in 0b00..=0b11 {
+                assert_eq!(
+                    Mnemonic::decode(field << 3 | dw, 0b000),
+                    Some((opcode, Encoding::RegMemWithReg))
+                );
+            }
+            for w in 0b0..=0b1 {
+                assert_eq!(
+                    Mnemonic::decode(field << 3 | 0b100 | w, 0b000),
+                    Some((opcode, Encoding::ImmediateToAccumulator))
+                );
+            }
+            for value in [0x80, 0x81, 0x83] {
+                assert_eq!(
+                    Mnemonic::decode(value, field),
+                    Some((opcode, Encoding::SignExtendedImmediateToRegMem))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_logical_encodings() {
+        let operations = [
+            (0b001, Mnemonic::Or),
+            (0b100, Mnemonic::And),
+            (0b110, Mnemonic::Xor),
+        ];
+        for (field, opcode) in operations {
+            for dw in 0b00..=0b11 {
+                assert_eq!(
+                    Mnemonic::decode(field << 3 | dw, 0b000),
+                    Some((opcode, Encoding::RegMemWithReg))
+                );
+            }
+            assert_eq!(
+                Mnemonic::decode(field << 3 | 0b101, 0b000),
+                Some((opcode, Encoding::ImmediateToAccumulator))
+            );
+            for value in [0x80, 0x81, 0x83] {
+                assert_eq!(
+                    Mnemonic::decode(value, field),
+                    Some((opcode, Encoding::SignExtendedImmediateToRegMem))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_test_encodings() {
+        assert_eq!(
+            Mnemonic::decode(0b10000101, 0b011),
+            Some((Mnemonic::Test, Encoding::RegMemWithReg))
+        );
+        assert_eq!(
+            Mnemonic::decode(0b11110110, 0b000),
+            Some((Mnemonic::Test, Encoding::ImmediateToRegMem))
+        );
+        assert_eq!(
+            Mnemonic::decode(0b10101001, 0b000),
+            Some((Mnemonic::Test, Encoding::ImmediateToAccumulator))
+        );
+    }
+
+    #[test]
+    fn test_decode_short_jumps() {
+        for value in 0x70..=0x7F {
+            let (_, encoding) = Mnemonic::decode(value, 0b000).unwrap();
+            assert_eq!(encoding, Encoding::ShortJump);
+        }
+        assert_eq!(Mnemonic::decode(0x74, 0b000).unwrap().0, Mnemonic::Jz);
+        assert_eq!(Mnemonic::decode(0x7F, 0b000).unwrap().0, Mnemonic::Jg);
+        assert_eq!(
+            Mnemonic::decode(0xE0, 0b000),
+            Some((Mnemonic::Loopnz, Encoding::ShortJump))
+        );
+        assert_eq!(
+            Mnemonic::decode(0xE3, 0b000),
+            Some((Mnemonic::Jcxz, Encoding::ShortJump))
+        );
+    }
+
+    #[test]
+    fn test_decode_call_jmp_ret() {
+        let cases = [
+            (0xE8, 0b000, Mnemonic::Call, Encoding::NearJump),
+            (0x9A, 0b000, Mnemonic::Call, Encoding::FarPointer),
+            (0xFF, 0b010, Mnemonic::Call, Encoding::RegMem),
+            (0xFF, 0b011, Mnemonic::Call, Encoding::FarRegMem),
+            (0xE9, 0b000, Mnemonic::Jmp, Encoding::NearJump),
+            (0xEB, 0b000, Mnemonic::Jmp, Encoding::ShortJump),
+            (0xEA, 0b000, Mnemonic::Jmp, Encoding::FarPointer),
+            (0xFF, 0b100, Mnemonic::Jmp, Encoding::RegMem),
+            (0xFF, 0b101, Mnemonic::Jmp, Encoding::FarRegMem),
+            (0xC3, 0b000, Mnemonic::Ret, Encoding::Implied),
+            (0xC2, 0b000, Mnemonic::Ret, Encoding::Immediate16),
+            (0xCB, 0b000, Mnemonic::Retf, Encoding::Implied),
+            (0xCA, 0b000, Mnemonic::Retf, Encoding::Immediate16),
+        ];
+        for (value, reg, opcode, encoding) in cases {
+            assert_eq!(Mnemonic::decode(value, reg), Some((opcode, encoding)));
+        }
+    }
+
+    #[test]
+    fn test_decode_stack() {
+        for value in 0x50..=0x57 {
+            assert_eq!(
+                Mnemonic::decode(value, 0b000),
+                Some((Mnemonic::Push, Encoding::Register))
+            );
+            assert_eq!(
+                Mnemonic::decode(value | 0b1000, 0b000),
+                Some((Mnemonic::Pop, Encoding::Register))
+            );
+        }
+        for sr in 0b00..=0b11 {
+            assert_eq!(
+                Mnemonic::decode(sr << 3 | 0b110, 0b000),
+                Some((Mnemonic::Push, Encoding::Segment))
+            );
+        }
+        // pop cs is not a documented 8086 instruction
+        assert_eq!(Mnemonic::decode(0x0F, 0b000), None);
+        assert_eq!(
+            Mnemonic::decode(0xFF, 0b110),
+            Some((Mnemonic::Push, Encoding::RegMem))
+        );
+        assert_eq!(
+            Mnemonic::decode(0x8F, 0b000),
+            Some((Mnemonic::Pop, Encoding::RegMem))
+        );
+        assert_eq!(Mnemonic::decode(0x9C, 0b000).unwrap().0, Mnemonic::Pushf);
+        assert_eq!(Mnemonic::decode(0x9D, 0b000).unwrap().0, Mnemonic::Popf);
+    }
+
+    #[test]
+    fn test_decode_unary_groups() {
+        let operations = [
+            (0b010, Mnemonic::Not),
+            (0b011, Mnemonic::Neg),
+            (0b100, Mnemonic::Mul),
+            (0b101, Mnemonic::Imul),
+            (0b110, Mnemonic::Div),
+            (0b111, Mnemonic::Idiv),
+        ];
+        for (reg, opcode) in operations {
+            assert_eq!(
+                Mnemonic::decode(0xF6, reg),
+                Some((opcode, Encoding::RegMem))
+            );
+            assert_eq!(
+                Mnemonic::decode(0xF7, reg),
+                Some((opcode, Encoding::RegMem))
+            );
+        }
+        assert_eq!(Mnemonic::decode(0xF6, 0b001), None);
+        for value in [0xFE, 0xFF] {
+            assert_eq!(
+                Mnemonic::decode(value, 0b000),
+                Some((Mnemonic::Inc, Encoding::RegMem))
+            );
+            assert_eq!(
+                Mnemonic::decode(value, 0b001),
+                Some((Mnemonic::Dec, Encoding::RegMem))
+            );
+        }
+        for reg in 0b010..=0b111 {
+            assert_eq!(Mnemonic::decode(0xFE, reg), None);
+        }
+        assert_eq!(
+            Mnemonic::decode(0x43, 0b000),
+            Some((Mnemonic::Inc, Encoding::Register))
+        );
+        assert_eq!(
+            Mnemonic::decode(0x4F, 0b000),
+            Some((Mnemonic::Dec, Encoding::Register))
+        );
+    }
+
+    #[test]
+    fn test_decode_shift_group() {
+        let operations = [
+            Mnemonic::Rol,
+            Mnemonic::Ror,
+            Mnemonic::Rcl,
+            Mnemonic::Rcr,
+            Mnemonic::Shl,
+            Mnemonic::Shr,
+            Mnemonic::Sal,
+            Mnemonic::Sar,
+        ];
+        for (reg, opcode) in operations.into_iter().enumerate() {
+            for value in 0xD0..=0xD3 {
+                assert_eq!(
+                    Mnemonic::decode_for_cpu(value, reg as u8, Cpu::Intel80186),
+                    Some((opcode, Encoding::Shift))
+                );
+            }
+        }
+        for value in 0xD0..=0xD3 {
+            assert_eq!(
+                Mnemonic::decode(value, 0b110),
+                Mnemonic::decode_undocumented(value, 0b110)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_string_instructions() {
+        for value in (0xA4..=0xA7).chain(0xAA..=0xAF) {
+            let (opcode, encoding) = Mnemonic::decode(value, 0b000).unwrap();
+            assert!(opcode.is_string());
+            assert_eq!(encoding, Encoding::Implied);
+        }
+        assert!(Mnemonic::Cmpsw.is_comparison());
+        assert!(Mnemonic::Scasb.is_comparison());
+        assert!(!Mnemonic::Movsb.is_comparison());
+        assert!(!Mnemonic::Mov.is_string());
+    }
+
+    #[test]
+    fn test_decode_port_instructions() {
+        let cases = [
+            (0xE4, Mnemonic::In, Encoding::FixedPort),
+            (0xE5, Mnemonic::In, Encoding::FixedPort),
+            (0xE6, Mnemonic::Out, Encoding::FixedPort),
+            (0xE7, Mnemonic::Out, Encoding::FixedPort),
+            (0xEC, Mnemonic::In, Encoding::VariablePort),
+            (0xED, Mnemonic::In, Encoding::VariablePort),
+            (0xEE, Mnemonic::Out, Encoding::VariablePort),
+            (0xEF, Mnemonic::Out, Encoding::VariablePort),
+        ];
+        for (value, opcode, encoding) in cases {
+            assert_eq!(Mnemonic::decode(value, 0b000), Some((opcode, encoding)));
+        }
+    }
+
+    #[test]
+    fn test_decode_interrupts() {
+        let cases = [
+            (0xCC, Mnemonic::Int3, Encoding::Implied),
+            (0xCD, Mnemonic::Int, Encoding::Immediate8),
+            (0xCE, Mnemonic::Into, Encoding::Implied),
+            (0xCF, Mnemonic::Iret, Encoding::Implied),
+        ];
+        for (value, opcode, encoding) in cases {
+            assert_eq!(Mnemonic::decode(value, 0b000), Some((opcode, encoding)));
+        }
+    }
+
+    #[test]
+    fn test_decode_single_byte_instructions() {
+        let cases = [
+            (0x27, Mnemonic::Daa),
+            (0x2F, Mnemonic::Das),
+            (0x37, Mnemonic::Aaa),
+            (0x3F, Mnemonic::Aas),
+            (0x90, Mnemonic::Nop),
+            (0x98, Mnemonic::Cbw),
+            (0x99, Mnemonic::Cwd),
+            (0x9B, Mnemonic::Wait),
+            (0x9E, Mnemonic::Sahf),
+            (0x9F, Mnemonic::Lahf),
+            (0xD7, Mnemonic::Xlatb),
+            (0xF4, Mnemonic::Hlt),
+            (0xF5, Mnemonic::Cmc),
+            (0xF8, Mnemonic::Clc),
+            (0xF9, Mnemonic::Stc),
+            (0xFA, Mnemonic::Cli),
+            (0xFB, Mnemonic::Sti),
+            (0xFC, Mnemonic::Cld),
+            (0xFD, Mnemonic::Std),
+        ];
+        for (value, opcode) in cases {
+            assert_eq!(
+                Mnemonic::decode(value, 0b000),
+                Some((opcode, Encoding::Implied))
+            );
+        }
+        assert_eq!(
+            Mnemonic::decode(0xD4, 0b000),
+            Some((Mnemonic::Aam, Encoding::Base))
+        );
+        assert_eq!(
+            Mnemonic::decode(0xD5, 0b000),
+            Some((Mnemonic::Aad, Encoding::Base))
+        );
+        assert!(Mnemonic::Xlatb.uses_implied_memory());
+        assert!(Mnemonic::Lodsw.uses_implied_memory());
+        assert!(!Mnemonic::Stosb.uses_implied_memory());
+        assert!(!Mnemonic::Nop.uses_implied_memory());
+    }
+
+    #[test]
+    fn test_decode_exchange_and_load_address() {
+        let cases = [
+            (0x86, Mnemonic::Xchg, Encoding::RegMemWithReg),
+            (0x87, Mnemonic::Xchg, Encoding::RegMemWithReg),
+            (0x91, Mnemonic::Xchg, Encoding::RegisterWithAccumulator),
+            (0x97, Mnemonic::Xchg, Encoding::RegisterWithAccumulator),
+            (0x8D, Mnemonic::Lea, Encoding::LoadAddress),
+            (0xC4, Mnemonic::Les, Encoding::LoadAddress),
+            (0xC5, Mnemonic::Lds, Encoding::LoadAddress),
+        ];
+        for (value, opcode, encoding) in cases {
+            assert_eq!(Mnemonic::decode(value, 0b000), Some((opcode, encoding)));
+        }
+        // xchg ax, ax is decoded as nop
+        assert_eq!(Mnemonic::decode(0x90, 0b000).unwrap().0, Mnemonic::Nop);
+    }
+
+    #[test]
+    fn test_decode_escape() {
+        for value in 0xD8..=0xDF {
+            for reg in 0b000..=0b111 {
+                assert_eq!(
+                    Mnemonic::decode(value, reg),
+                    Some((Mnemonic::Esc, Encoding::Escape))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_80186_extensions() {
+        let cases = [
+            (0x60, Mnemonic::Pusha, Encoding::Implied),
+            (0x61, Mnemonic::Popa, Encoding::Implied),
+            (0x62, Mnemonic::Bound, Encoding::LoadAddress),
+            (0x68, Mnemonic::Push, Encoding::SignExtendedImmediate),
+            (0x6A, Mnemonic::Push, Encoding::SignExtendedImmediate),
+            (0x69, Mnemonic::Imul, Encoding::RegMemWithImmediate),
+            (0x6B, Mnemonic::Imul, Encoding::RegMemWithImmediate),
+            (0x6C, Mnemonic::Insb, Encoding::Implied),
+            (0x6F, Mnemonic::Outsw, Encoding::Implied),
+            (0xC0, Mnemonic::Rol, Encoding::ShiftImmediate),
+            (0xC8, Mnemonic::Enter, Encoding::StackFrame),
+            (0xC9, Mnemonic::Leave, Encoding::Implied),
+        ];
+        for (value, opcode, encoding) in cases {
+            assert_eq!(
+                Mnemonic::decode_for_cpu(value, 0b000, Cpu::Intel80186),
+                Some((opcode, encoding))
+            );
+            assert_eq!(Mnemonic::decode_for_cpu(value, 0b000, Cpu::Intel8086), None);
+        }
+        assert_eq!(
+            Mnemonic::decode_for_cpu(0xC1, 0b101, Cpu::Intel80186),
+            Some((Mnemonic::Shr, Encoding::ShiftImmediate))
+        );
+        assert_eq!(
+            Mnemonic::decode_for_cpu(0x89, 0b000, Cpu::Intel80186),
+            Some((Mnemonic::Mov, Encoding::RegMemWithReg))
+        );
+        assert!(Mnemonic::Outsb.is_string());
+    }
+
+    #[test]
+    fn test_decode_80286_extensions() {
+        let cases = [
+            (0x00, 0b000, Mnemonic::Sldt, Encoding::WordRegMem),
+            (0x00, 0b010, Mnemonic::Lldt, Encoding::WordRegMem),
+            (0x00, 0b011, Mnemonic::Ltr, Encoding::WordRegMem),
+            (0x00, 0b100, Mnemonic::Verr, Encoding::WordRegMem),
+            (0x00, 0b101, Mnemonic::Verw, Encoding::WordRegMem),
+            (0x01, 0b000, Mnemonic::Sgdt, Encoding::Memory),
+            (0x01, 0b011, Mnemonic::Lidt, Encoding::Memory),
+            (0x01, 0b100, Mnemonic::Smsw, Encoding::WordRegMem),
+            (0x01, 0b110, Mnemonic::Lmsw, Encoding::WordRegMem),
+            (0x02, 0b000, Mnemonic::Lar, Encoding::WordRegWithRegMem),
+            (0x03, 0b111, Mnemonic::Lsl, Encoding::WordRegWithRegMem),
+            (0x06, 0b000, Mnemonic::Clts, Encoding::Implied),
+        ];
+        for (value, reg, opcode, encoding) in cases {
+            assert_eq!(
+                Mnemonic::decode_two_byte_for_cpu(value, reg, Cpu::Intel80286),
+                Some((opcode, encoding))
+            );
+            assert_eq!(
+                Mnemonic::decode_two_byte_for_cpu(value, reg, Cpu::Intel80186),
+                None
+            );
+        }
+        for (value, reg) in [(0x00, 0b110), (0x01, 0b101), (0x05, 0b000), (0xFF, 0b000)] {
+            assert_eq!(
+                Mnemonic::decode_two_byte_for_cpu(value, reg, Cpu::Intel80286),
+                None
+            );
+        }
+        assert_eq!(
+            Mnemonic::decode_for_cpu(0x63, 0b000, Cpu::Intel80286),
+            Some((Mnemonic::Arpl, Encoding::WordRegMemWithReg))
+        );
+        assert_eq!(
+            Mnemonic::decode_for_cpu(0x60, 0b000, Cpu::Intel80286),
+            Some((Mnemonic::Pusha, Encoding::Implied))
+        );
+        assert_eq!(Mnemonic::decode_for_cpu(0x63, 0b000, Cpu::Intel80186), None);
+    }
+
+    #[test]
+    fn test_decode_v20_extensions() {
+        let cases = [
+            (0x10, 0b000, Mnemonic::Test1, Encoding::BitCl),
+            (0x13, 0b000, Mnemonic::Clr1, Encoding::BitCl),
+            (0x14, 0b000, Mnemonic::Set1, Encoding::BitCl),
+            (0x17, 0b000, Mnemonic::Not1, Encoding::BitCl),
+            (0x19, 0b000, Mnemonic::Test1, Encoding::BitImmediate),
+            (0x1A, 0b000, Mnemonic::Clr1, Encoding::BitImmediate),
+            (0x1D, 0b000, Mnemonic::Set1, Encoding::BitImmediate),
+            (0x1E, 0b000, Mnemonic::Not1, Encoding::BitImmediate),
+            (0x20, 0b000, Mnemonic::Add4s, Encoding::Implied),
+            (0x22, 0b000, Mnemonic::Sub4s, Encoding::Implied),
+            (0x26, 0b000, Mnemonic::Cmp4s, Encoding::Implied),
+            (0x28, 0b000, Mnemonic::Rol4, Encoding::RegMem),
+            (0x2A, 0b000, Mnemonic::Ror4, Encoding::RegMem),
+            (0x31, 0b011, Mnemonic::Ins, Encoding::ByteRegisters),
+            (0x33, 0b011, Mnemonic::Ext, Encoding::ByteRegisters),
+            (
+                0x39,
+                0b000,
+                Mnemonic::Ins,
+                Encoding::ByteRegisterWithImmediate,
+            ),
+            (
+                0x3B,
+                0b000,
+                Mnemonic::Ext,
+                Encoding::ByteRegisterWithImmediate,
+            ),
+            (0xFF, 0b000, Mnemonic::Brkem, Encoding::Immediate8),
+        ];
+        for (value, reg, opcode, encoding) in cases {
+            assert_eq!(
+                Mnemonic::decode_two_byte_for_cpu(value, reg, Cpu::NecV20),
+                Some((opcode, encoding))
+            );
+            assert_eq!(
+                Mnemonic::decode_two_byte_for_cpu(value, reg, Cpu::Intel80286),
+                None
+            );
+        }
+        for (value, reg) in [(0x28, 0b001), (0x39, 0b010), (0x21, 0b000), (0x00, 0b000)] {
+            assert_eq!(
+                Mnemonic::decode_two_byte_for_cpu(value, reg, Cpu::NecV20),
+                None
+            );
+        }
+        assert_eq!(
+            Mnemonic::decode_for_cpu(0xC8, 0b000, Cpu::NecV20),
+            Some((Mnemonic::Enter, Encoding::StackFrame))
+        );
+        assert_eq!(Mnemonic::decode_for_cpu(0x63, 0b000, Cpu::NecV20), None);
+    }
+
+    #[test]
+    fn test_decode_undocumented() {
+        let cases = [
+            (0x0F, 0b000, Mnemonic::Pop, Encoding::Segment),
+            (0x60, 0b000, Mnemonic::Jo, Encoding::ShortJump),
+            (0x64, 0b000, Mnemonic::Jz, Encoding::ShortJump),
+            (0x6F, 0b000, Mnemonic::Jg, Encoding::ShortJump),
+            (
+                0x82,
+                0b001,
+                Mnemonic::Or,
+                Encoding::SignExtendedImmediateToRegMem,
+            ),
+            (
+                0x82,
+                0b111,
+                Mnemonic::Cmp,
+                Encoding::SignExtendedImmediateToRegMem,
+            ),
+            (0xC0, 0b000, Mnemonic::Ret, Encoding::Immediate16),
+            (0xC1, 0b000, Mnemonic::Ret, Encoding::Implied),
+            (0xC8, 0b000, Mnemonic::Retf, Encoding::Immediate16),
+            (0xC9, 0b000, Mnemonic::Retf, Encoding::Implied),
+            (0xD6, 0b000, Mnemonic::Salc, Encoding::Implied),
+        ];
+        for (value, reg, opcode, encoding) in cases {
+            assert_eq!(
+                Mnemonic::decode_for_cpu(value, reg, Cpu::Intel8086Silicon),
+                Some((opcode, encoding))
+            );
+            assert_eq!(Mnemonic::decode_for_cpu(value, reg, Cpu::Intel8086), None);
+        }
+        // SETMO has no documented meaning, so the 8086 mode decodes it as well
+        let setmo = [
+            (0xD0, Mnemonic::Setmo, Encoding::RegMem),
+            (0xD3, Mnemonic::Setmoc, Encoding::BitCl),
+        ];
+        for (value, opcode, encoding) in setmo {
+            for cpu in [Cpu::Intel8086, Cpu::Intel8086Silicon] {
+                assert_eq!(
+                    Mnemonic::decode_for_cpu(value, 0b110, cpu),
+                    Some((opcode, encoding))
+                );
+            }
+        }
+        assert_eq!(Mnemonic::decode_undocumented(0x70, 0b000), None);
     }
 }
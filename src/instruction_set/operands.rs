@@ -0,0 +1,346 @@
+use std::fmt;
+use std::ops::Deref;
+
+use super::registers::Register;
+
+/// The width of a memory operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OperandSize {
+    /// 8 bits
+    Byte,
+    /// 16 bits
+    Word,
+    /// 32 bits, an 8087 short real or integer
+    Dword,
+    /// 64 bits, an 8087 long real or integer
+    Qword,
+    /// 80 bits, an 8087 temporary real or packed decimal
+    Tword,
+    /// A far pointer, a 16-bit offset followed by a 16-bit segment
+    Far,
+}
+
+impl fmt::Display for OperandSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match self {
+            OperandSize::Byte => "byte",
+            OperandSize::Word => "word",
+            OperandSize::Dword => "dword",
+            OperandSize::Qword => "qword",
+            OperandSize::Tword => "tword",
+            OperandSize::Far => "far",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// A memory operand built from the 8086 effective-address table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EffectiveAddress {
+    /// The segment override, if the default segment is not used
+    pub segment: Option<Register>,
+    /// The base register, `bx` or `bp`
+    pub base: Option<Register>,
+    /// The index register, `si` or `di`
+    pub index: Option<Register>,
+    /// The displacement, or the whole address when there is neither base nor index
+    pub displacement: i16,
+    /// The width of the access when neither the instruction nor a register operand implies it
+    pub size: Option<OperandSize>,
+}
+
+impl EffectiveAddress {
+    /// Constructs an EffectiveAddress from the R/M field and its sign-extended displacement.
+    /// The mod=00 r/m=110 direct address is built with `EffectiveAddress::direct` instead.
+    pub(crate) fn from_rm_field(rm: u8, displacement: i16) -> Self {
+        let (base, index) = match rm & 0b111 {
+            0b000 => (Some(Register::BX), Some(Register::SI)),
+            0b001 => (Some(Register::BX), Some(Register::DI)),
+            0b010 => (Some(Register::BP), Some(Register::SI)),
+            0b011 => (Some(Register::BP), Some(Register::DI)),
+            0b100 => (None, Some(Register::SI)),
+            0b101 => (None, Some(Register::DI)),
+            0b110 => (Some(Register::BP), None),
+            _ => (Some(Register::BX), None),
+        };
+        EffectiveAddress {
+            segment: None,
+            base,
+            index,
+            displacement,
+            size: None,
+        }
+    }
+
+    /// Constructs a direct 16-bit address without base or index register
+    pub(crate) fn direct(address: u16) -> Self {
+        EffectiveAddress {
+            segment: None,
+            base: None,
+            index: None,
+            displacement: address as i16,
+            size: None,
+        }
+    }
+
+    /// Overrides the default segment the address is relative to
+    pub(crate) fn with_segment(self, segment: Option<Register>) -> Self {
+        EffectiveAddress { segment, ..self }
+    }
+
+    /// Spells out the width of the access
+    pub(crate) fn with_size(self, size: OperandSize) -> Self {
+        EffectiveAddress {
+            size: Some(size),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for EffectiveAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(size) = self.size {
+            write!(f, "{} ", size)?;
+        }
+        if let Some(segment) = self.segment {
+            write!(f, "{}:", segment)?;
+        }
+        let registers: Vec<String> = [self.base, self.index]
+            .iter()
+            .flatten()
+            .map(|register| register.to_string())
+            .collect();
+        if registers.is_empty() {
+            return write!(f, "[{}]", self.displacement as u16);
+        }
+        write!(f, "[{}", registers.join(" + "))?;
+        match self.displacement {
+            0 => {}
+            displacement if displacement < 0 => write!(f, " - {}", displacement.unsigned_abs())?,
+            displacement => write!(f, " + {}", displacement)?,
+        }
+        write!(f, "]")
+    }
+}
+
+/// An operand of a decoded instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Operand {
+    /// A general purpose, segment or 8087 stack register
+    Register(Register),
+    /// A memory location
+    Memory(EffectiveAddress),
+    /// Immediate data as the instruction interprets it, sign-extended where it is signed
+    Immediate(i32),
+    /// The displacement of a jump target from the end of the instruction
+    RelativeTarget(i16),
+    /// A direct far address given as segment and offset
+    FarPointer {
+        /// The segment the offset is counted in
+        segment: u16,
+        /// The offset within the segment
+        offset: u16,
+    },
+}
+
+impl Operand {
+    /// Spells out the width of a memory operand, leaving other operands as they are
+    pub(crate) fn with_size(self, size: OperandSize) -> Self {
+        match self {
+            Operand::Memory(address) => Operand::Memory(address.with_size(size)),
+            operand => operand,
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    /// Relative targets print as their signed displacement, since where they point to depends
+    /// on the instruction they belong to
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{}", register),
+            Operand::Memory(address) => write!(f, "{}", address),
+            Operand::Immediate(value) => write!(f, "{}", value),
+            Operand::RelativeTarget(displacement) => write!(f, "{:+}", displacement),
+            Operand::FarPointer { segment, offset } => write!(f, "{}:{}", segment, offset),
+        }
+    }
+}
+
+/// The most operands an instruction has, as in `imul ax, [bx], 10`
+pub(crate) const MAX_OPERANDS: usize = 3;
+
+/// The operands of an instruction, stored inline so decoding allocates nothing. They
+/// dereference to a slice in the order they are written, destination first.
+#[derive(Clone, Copy)]
+pub struct Operands {
+    operands: [Operand; MAX_OPERANDS],
+    length: usize,
+}
+
+impl Operands {
+    /// Appends an operand after the ones already present
+    pub(crate) fn push(&mut self, operand: Operand) {
+        self.operands[self.length] = operand;
+        self.length += 1;
+    }
+}
+
+impl Default for Operands {
+    fn default() -> Self {
+        Operands {
+            operands: [Operand::Immediate(0); MAX_OPERANDS],
+            length: 0,
+        }
+    }
+}
+
+impl<const N: usize> From<[Operand; N]> for Operands {
+    fn from(operands: [Operand; N]) -> Self {
+        let mut list = Operands::default();
+        for operand in operands {
+            list.push(operand);
+        }
+        list
+    }
+}
+
+impl Deref for Operands {
+    type Target = [Operand];
+
+    fn deref(&self) -> &[Operand] {
+        &self.operands[..self.length]
+    }
+}
+
+impl PartialEq for Operands {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for Operands {}
+
+impl<const N: usize> PartialEq<[Operand; N]> for Operands {
+    fn eq(&self, other: &[Operand; N]) -> bool {
+        **self == *other
+    }
+}
+
+impl fmt::Debug for Operands {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_effective_address_table() {
+        let expected = [
+            "[bx + si]",
+            "[bx + di]",
+            "[bp + si]",
+            "[bp + di]",
+            "[si]",
+            "[di]",
+            "[bp]",
+            "[bx]",
+        ];
+        for (rm, text) in expected.iter().enumerate() {
+            let address = EffectiveAddress::from_rm_field(rm as u8, 0);
+            assert_eq!(format!("{}", address), *text);
+        }
+    }
+
+    #[test]
+    fn test_effective_address_displacement() {
+        assert_eq!(
+            format!("{}", EffectiveAddress::from_rm_field(0b011, 8)),
+            "[bp + di + 8]"
+        );
+        assert_eq!(
+            format!("{}", EffectiveAddress::from_rm_field(0b001, -37)),
+            "[bx + di - 37]"
+        );
+        assert_eq!(
+            format!("{}", EffectiveAddress::from_rm_field(0b100, i16::MIN)),
+            "[si - 32768]"
+        );
+    }
+
+    #[test]
+    fn test_direct_address() {
+        assert_eq!(format!("{}", EffectiveAddress::direct(3458)), "[3458]");
+        assert_eq!(format!("{}", EffectiveAddress::direct(0xFFFF)), "[65535]");
+    }
+
+    #[test]
+    fn test_segment_override() {
+        let address = EffectiveAddress::from_rm_field(0b000, 0).with_segment(Some(Register::ES));
+        assert_eq!(format!("{}", address), "es:[bx + si]");
+        let address = EffectiveAddress::direct(16).with_segment(Some(Register::CS));
+        assert_eq!(format!("{}", address), "cs:[16]");
+        let address = EffectiveAddress::direct(16).with_segment(None);
+        assert_eq!(format!("{}", address), "[16]");
+    }
+
+    #[test]
+    fn test_sized_address() {
+        let address = EffectiveAddress::from_rm_field(0b111, 2)
+            .with_segment(Some(Register::ES))
+            .with_size(OperandSize::Byte);
+        assert_eq!(format!("{}", address), "byte es:[bx + 2]");
+        let address = EffectiveAddress::direct(16).with_size(OperandSize::Tword);
+        assert_eq!(format!("{}", address), "tword [16]");
+        let operand = Operand::Register(Register::AX).with_size(OperandSize::Far);
+        assert_eq!(operand, Operand::Register(Register::AX));
+    }
+
+    #[test]
+    fn test_operand_display() {
+        assert_eq!(format!("{}", Operand::Register(Register::CX)), "cx");
+        assert_eq!(
+            format!(
+                "{}",
+                Operand::Memory(EffectiveAddress::from_rm_field(0b111, 0))
+            ),
+            "[bx]"
+        );
+        assert_eq!(format!("{}", Operand::Immediate(-7)), "-7");
+        assert_eq!(format!("{}", Operand::RelativeTarget(4)), "+4");
+        assert_eq!(
+            format!(
+                "{}",
+                Operand::FarPointer {
+                    segment: 0xF000,
+                    offset: 0xFFF0
+                }
+            ),
+            "61440:65520"
+        );
+    }
+
+    #[test]
+    fn test_operand_list() {
+        let mut operands = Operands::default();
+        assert!(operands.is_empty());
+        operands.push(Operand::Register(Register::AX));
+        operands.push(Operand::Immediate(3));
+        assert_eq!(operands.len(), 2);
+        assert_eq!(
+            operands,
+            [Operand::Register(Register::AX), Operand::Immediate(3)]
+        );
+        assert_eq!(
+            operands,
+            Operands::from([Operand::Register(Register::AX), Operand::Immediate(3)])
+        );
+        assert_eq!(format!("{:?}", operands), "[Register(AX), Immediate(3)]");
+    }
+}
@@ -0,0 +1,134 @@
+use super::{cpu::Cpu, errors::DecodeError, registers::Register};
+
+/// A repeat prefix in front of a string instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Repeat {
+    /// `F3`, printed as `rep` or, for comparing string instructions, `repe`
+    Rep,
+    /// `F2`, printed as `repne`
+    Repne,
+}
+
+/// The prefix bytes decoded in front of an opcode. Each kind may appear at most once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Prefixes {
+    /// The repeat prefix of a string instruction
+    pub repeat: Option<Repeat>,
+    /// The segment override, which the memory operand of the instruction also carries
+    pub segment: Option<Register>,
+    /// Whether the instruction is locked, by `F0` or its alias
+    pub lock: bool,
+    /// Whether the lock was given as `F1`, which 8086 silicon treats like `F0`
+    pub lock_alias: bool,
+}
+
+impl Prefixes {
+    /// Accumulate the prefixes at the start of `buffer`, returning them with their byte count.
+    /// `F1` only counts as a prefix on a `cpu` that decodes undocumented opcodes.
+    pub(crate) fn decode(buffer: &[u8], cpu: Cpu) -> Result<(Self, usize), DecodeError> {
+        let mut prefixes = Prefixes::default();
+        let mut length = 0;
+        while let Some(&byte) = buffer.get(length) {
+            let duplicate = match byte {
+                0xF0 => std::mem::replace(&mut prefixes.lock, true),
+                0xF1 if cpu.has_undocumented_opcodes() => {
+                    prefixes.lock_alias = true;
+                    std::mem::replace(&mut prefixes.lock, true)
+                }
+                0xF2 => prefixes.repeat.replace(Repeat::Repne).is_some(),
+                0xF3 => prefixes.repeat.replace(Repeat::Rep).is_some(),
+                0x26 | 0x2E | 0x36 | 0x3E => {
+                    let segment = Register::from_sr_field((byte & 0b00011000) >> 3)?;
+                    prefixes.segment.replace(segment).is_some()
+                }
+                _ => break,
+            };
+            if duplicate {
+                return Err(DecodeError::DuplicatePrefix);
+            }
+            length += 1;
+        }
+        Ok((prefixes, length))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_without_prefixes() {
+        assert_eq!(
+            Prefixes::decode(&[0xA4], Cpu::Intel8086),
+            Ok((Prefixes::default(), 0))
+        );
+        assert_eq!(
+            Prefixes::decode(&[], Cpu::Intel8086),
+            Ok((Prefixes::default(), 0))
+        );
+    }
+
+    #[test]
+    fn test_decode_repeat_prefixes() {
+        let (prefixes, length) = Prefixes::decode(&[0xF3, 0xA4], Cpu::Intel8086).unwrap();
+        assert_eq!(prefixes.repeat, Some(Repeat::Rep));
+        assert_eq!(length, 1);
+        let (prefixes, length) = Prefixes::decode(&[0xF2, 0xAF], Cpu::Intel8086).unwrap();
+        assert_eq!(prefixes.repeat, Some(Repeat::Repne));
+        assert_eq!(length, 1);
+    }
+
+    #[test]
+    fn test_decode_segment_override_prefixes() {
+        let segments = [
+            (0x26, Register::ES),
+            (0x2E, Register::CS),
+            (0x36, Register::SS),
+            (0x3E, Register::DS),
+        ];
+        for (byte, segment) in segments {
+            let (prefixes, length) = Prefixes::decode(&[byte, 0x8B, 0x00], Cpu::Intel8086).unwrap();
+            assert_eq!(prefixes.segment, Some(segment));
+            assert_eq!(length, 1);
+        }
+    }
+
+    #[test]
+    fn test_decode_combined_prefixes() {
+        let (prefixes, length) =
+            Prefixes::decode(&[0xF0, 0x26, 0xF3, 0xA4], Cpu::Intel8086).unwrap();
+        assert!(prefixes.lock);
+        assert_eq!(prefixes.segment, Some(Register::ES));
+        assert_eq!(prefixes.repeat, Some(Repeat::Rep));
+        assert_eq!(length, 3);
+    }
+
+    #[test]
+    fn test_decode_duplicate_prefixes() {
+        for bytes in [[0xF3, 0xF2, 0xA4], [0x26, 0x2E, 0x8B], [0xF0, 0xF0, 0x01]] {
+            assert_eq!(
+                Prefixes::decode(&bytes, Cpu::Intel8086),
+                Err(DecodeError::DuplicatePrefix)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_lock_alias() {
+        let (prefixes, length) =
+            Prefixes::decode(&[0xF1, 0x01, 0x07], Cpu::Intel8086Silicon).unwrap();
+        assert!(prefixes.lock);
+        assert!(prefixes.lock_alias);
+        assert_eq!(length, 1);
+        assert_eq!(
+            Prefixes::decode(&[0xF1, 0x01, 0x07], Cpu::Intel8086),
+            Ok((Prefixes::default(), 0))
+        );
+        assert_eq!(
+            Prefixes::decode(&[0xF0, 0xF1, 0x01], Cpu::Intel8086Silicon),
+            Err(DecodeError::DuplicatePrefix)
+        );
+    }
+}
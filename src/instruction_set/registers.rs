@@ -1,77 +1,117 @@
 use std::fmt;
 
-use super::{bit::Bit, errors::X86InstructionError};
+use super::{bit::Bit, errors::DecodeError};
 
-#[derive(Debug, PartialEq)]
-pub enum X86Register {
+/// A register operand, displayed as its lowercase name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Register {
+    /// Low byte of AX
     AL,
+    /// Low byte of CX
     CL,
+    /// Low byte of DX
     DL,
+    /// Low byte of BX
     BL,
+    /// High byte of AX
     AH,
+    /// High byte of CX
     CH,
+    /// High byte of DX
     DH,
+    /// High byte of BX
     BH,
+    /// Accumulator
     AX,
+    /// Count register
     CX,
+    /// Data register
     DX,
+    /// Base register
     BX,
+    /// Stack pointer
     SP,
+    /// Base pointer
     BP,
+    /// Source index
     SI,
+    /// Destination index
     DI,
-    InvalidRegister,
+    /// Extra segment
+    ES,
+    /// Code segment
+    CS,
+    /// Stack segment
+    SS,
+    /// Data segment
+    DS,
+    /// A register of the 8087 stack, counted from its top
+    St(u8),
 }
 
-impl fmt::Display for X86Register {
+impl fmt::Display for Register {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let value = match self {
-            X86Register::AL => "al",
-            X86Register::CL => "cl",
-            X86Register::DL => "dl",
-            X86Register::BL => "bl",
-            X86Register::AH => "ah",
-            X86Register::CH => "ch",
-            X86Register::DH => "dh",
-            X86Register::BH => "bh",
-            X86Register::AX => "ax",
-            X86Register::CX => "cx",
-            X86Register::DX => "dx",
-            X86Register::BX => "bx",
-            X86Register::SP => "sp",
-            X86Register::BP => "bp",
-            X86Register::SI => "si",
-            X86Register::DI => "di",
-            X86Register::InvalidRegister => "invalid",
+            Register::AL => "al",
+            Register::CL => "cl",
+            Register::DL => "dl",
+            Register::BL => "bl",
+            Register::AH => "ah",
+            Register::CH => "ch",
+            Register::DH => "dh",
+            Register::BH => "bh",
+            Register::AX => "ax",
+            Register::CX => "cx",
+            Register::DX => "dx",
+            Register::BX => "bx",
+            Register::SP => "sp",
+            Register::BP => "bp",
+            Register::SI => "si",
+            Register::DI => "di",
+            Register::ES => "es",
+            Register::CS => "cs",
+            Register::SS => "ss",
+            Register::DS => "ds",
+            Register::St(index) => return write!(f, "st({})", index),
         };
         write!(f, "{}", value)
     }
 }
 
-impl X86Register {
-    // Constructs an X86Register from the W and reg fields.
+impl Register {
+    // Constructs a Register from the W and reg fields.
     // Here `w` is assumed to be either 0 or 1, and `reg` is assumed to be a value from 0 to 7.
-    pub fn from_w_and_field(w: Bit, field: u8) -> Result<Self, X86InstructionError> {
+    pub(crate) fn from_w_and_field(w: Bit, field: u8) -> Result<Self, DecodeError> {
         match (w, field) {
-            (Bit(false), 0b000) => Ok(X86Register::AL),
-            (Bit(false), 0b001) => Ok(X86Register::CL),
-            (Bit(false), 0b010) => Ok(X86Register::DL),
-            (Bit(false), 0b011) => Ok(X86Register::BL),
-            (Bit(false), 0b100) => Ok(X86Register::AH),
-            (Bit(false), 0b101) => Ok(X86Register::CH),
-            (Bit(false), 0b110) => Ok(X86Register::DH),
-            (Bit(false), 0b111) => Ok(X86Register::BH),
-            (Bit(true), 0b000) => Ok(X86Register::AX),
-            (Bit(true), 0b001) => Ok(X86Register::CX),
-            (Bit(true), 0b010) => Ok(X86Register::DX),
-            (Bit(true), 0b011) => Ok(X86Register::BX),
-            (Bit(true), 0b100) => Ok(X86Register::SP),
-            (Bit(true), 0b101) => Ok(X86Register::BP),
-            (Bit(true), 0b110) => Ok(X86Register::SI),
-            (Bit(true), 0b111) => Ok(X86Register::DI),
-            (_, _) => {
-                Err(X86Register::InvalidRegister).map_err(|_| X86InstructionError::InvalidRegister)
-            }
+            (Bit(false), 0b000) => Ok(Register::AL),
+            (Bit(false), 0b001) => Ok(Register::CL),
+            (Bit(false), 0b010) => Ok(Register::DL),
+            (Bit(false), 0b011) => Ok(Register::BL),
+            (Bit(false), 0b100) => Ok(Register::AH),
+            (Bit(false), 0b101) => Ok(Register::CH),
+            (Bit(false), 0b110) => Ok(Register::DH),
+            (Bit(false), 0b111) => Ok(Register::BH),
+            (Bit(true), 0b000) => Ok(Register::AX),
+            (Bit(true), 0b001) => Ok(Register::CX),
+            (Bit(true), 0b010) => Ok(Register::DX),
+            (Bit(true), 0b011) => Ok(Register::BX),
+            (Bit(true), 0b100) => Ok(Register::SP),
+            (Bit(true), 0b101) => Ok(Register::BP),
+            (Bit(true), 0b110) => Ok(Register::SI),
+            (Bit(true), 0b111) => Ok(Register::DI),
+            (_, _) => Err(DecodeError::InvalidRegister),
+        }
+    }
+
+    // Constructs a segment Register from the 2-bit SR field.
+    pub(crate) fn from_sr_field(field: u8) -> Result<Self, DecodeError> {
+        match field {
+            0b00 => Ok(Register::ES),
+            0b01 => Ok(Register::CS),
+            0b10 => Ok(Register::SS),
+            0b11 => Ok(Register::DS),
+            _ => Err(DecodeError::InvalidRegister),
         }
     }
 }
@@ -85,23 +125,19 @@ mod test {
         // Test for W bit false (8-bit registers)
         for field in 0b000..=0b1000 {
             let w = Bit(false);
-            let register = if field > 0b111 {
-                X86Register::InvalidRegister
-            } else {
-                X86Register::from_w_and_field(w, field).unwrap()
-            };
+            let register = Register::from_w_and_field(w, field);
 
             // Determine the expected register enum variant based on the field
             let expected = match field {
-                0b000 => X86Register::AL,
-                0b001 => X86Register::CL,
-                0b010 => X86Register::DL,
-                0b011 => X86Register::BL,
-                0b100 => X86Register::AH,
-                0b101 => X86Register::CH,
-                0b110 => X86Register::DH,
-                0b111 => X86Register::BH,
-                _ => X86Register::InvalidRegister,
+                0b000 => Ok(Register::AL),
+                0b001 => Ok(Register::CL),
+                0b010 => Ok(Register::DL),
+                0b011 => Ok(Register::BL),
+                0b100 => Ok(Register::AH),
+                0b101 => Ok(Register::CH),
+                0b110 => Ok(Register::DH),
+                0b111 => Ok(Register::BH),
+                _ => Err(DecodeError::InvalidRegister),
             };
 
             assert_eq!(register, expected);
@@ -113,63 +149,71 @@ mod test {
         // Test for W bit true (16-bit registers)
         for field in 0b000..=0b1111 {
             let w = Bit(true);
-            let register = if field > 0b111 {
-                X86Register::InvalidRegister
-            } else {
-                X86Register::from_w_and_field(w, field).unwrap()
-            };
+            let register = Register::from_w_and_field(w, field);
 
             // Determine the expected register enum variant based on the field
             let expected = match field {
-                0b000 => X86Register::AX,
-                0b001 => X86Register::CX,
-                0b010 => X86Register::DX,
-                0b011 => X86Register::BX,
-                0b100 => X86Register::SP,
-                0b101 => X86Register::BP,
-                0b110 => X86Register::SI,
-                0b111 => X86Register::DI,
-                _ => X86Register::InvalidRegister,
+                0b000 => Ok(Register::AX),
+                0b001 => Ok(Register::CX),
+                0b010 => Ok(Register::DX),
+                0b011 => Ok(Register::BX),
+                0b100 => Ok(Register::SP),
+                0b101 => Ok(Register::BP),
+                0b110 => Ok(Register::SI),
+                0b111 => Ok(Register::DI),
+                _ => Err(DecodeError::InvalidRegister),
             };
 
             assert_eq!(register, expected);
         }
-        let invalid_result = X86Register::from_w_and_field(Bit(true), 0b1000);
-        assert_eq!(invalid_result, Err(X86InstructionError::InvalidRegister));
+        let invalid_result = Register::from_w_and_field(Bit(true), 0b1000);
+        assert_eq!(invalid_result, Err(DecodeError::InvalidRegister));
     }
 
     #[test]
     fn test_invalid_register() {
-        let invalid_result = X86Register::from_w_and_field(Bit(false), 0b10101); // value not covered
-        assert!(matches!(
-            invalid_result,
-            Err(X86InstructionError::InvalidRegister)
-        ));
-
-        let invalid_result = X86Register::from_w_and_field(Bit(true), 0b11000); // value not covered
-        assert!(matches!(
-            invalid_result,
-            Err(X86InstructionError::InvalidRegister)
-        ));
+        let invalid_result = Register::from_w_and_field(Bit(false), 0b10101); // value not covered
+        assert!(matches!(invalid_result, Err(DecodeError::InvalidRegister)));
+
+        let invalid_result = Register::from_w_and_field(Bit(true), 0b11000); // value not covered
+        assert!(matches!(invalid_result, Err(DecodeError::InvalidRegister)));
+    }
+
+    #[test]
+    fn test_from_sr_field() {
+        let expected = [
+            (Register::ES, "es"),
+            (Register::CS, "cs"),
+            (Register::SS, "ss"),
+            (Register::DS, "ds"),
+        ];
+        for (field, (register, name)) in expected.into_iter().enumerate() {
+            let decoded = Register::from_sr_field(field as u8).unwrap();
+            assert_eq!(decoded, register);
+            assert_eq!(format!("{}", decoded), name);
+        }
+        assert_eq!(
+            Register::from_sr_field(0b100),
+            Err(DecodeError::InvalidRegister)
+        );
     }
 
     #[test]
-    fn test_invalid_register_display() {
-        let register = X86Register::InvalidRegister;
-        let display_string = format!("{}", register);
-        assert_eq!(display_string, "invalid");
+    fn test_stack_register_display() {
+        assert_eq!(format!("{}", Register::St(0)), "st(0)");
+        assert_eq!(format!("{}", Register::St(7)), "st(7)");
     }
 
     #[test]
     fn test_debug_trait_for_register() {
-        let ax = X86Register::AX;
-        let bx = X86Register::BX;
-        let invalid_register = X86Register::InvalidRegister;
+        let ax = Register::AX;
+        let bx = Register::BX;
+        let stack_register = Register::St(3);
         let ax_debug_string = format!("{:?}", ax);
         let bx_debug_string = format!("{:?}", bx);
-        let invalid_register_debug_string = format!("{:?}", invalid_register);
+        let stack_register_debug_string = format!("{:?}", stack_register);
         assert_eq!(ax_debug_string, "AX");
         assert_eq!(bx_debug_string, "BX");
-        assert_eq!(invalid_register_debug_string, "InvalidRegister");
+        assert_eq!(stack_register_debug_string, "St(3)");
     }
 }
@@ -0,0 +1,281 @@
+use super::{
+    opcodes::Mnemonic,
+    operands::{Operand, OperandSize, Operands},
+    registers::Register,
+};
+
+/// The `wait` opcode, which folds into a directly following control instruction
+pub(crate) const WAIT: u8 = 0x9B;
+
+/// The operands of an 8087 instruction encoded in the ModRM byte following an ESC opcode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum X87Operands {
+    /// The instruction works on the top of the register stack only
+    None,
+    /// A memory operand of the given size, or without size for the environment and state areas
+    Memory(Option<OperandSize>),
+    /// A single stack register `st(i)`
+    Register,
+    /// The top of the stack as destination, `st(0), st(i)`
+    StackTopWithRegister,
+    /// The top of the stack as source, `st(i), st(0)`
+    RegisterWithStackTop,
+}
+
+/// An 8087 instruction, decoded from the low three bits of the ESC opcode and the ModRM byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct X87Instruction {
+    mnemonic: Mnemonic,
+    operands: X87Operands,
+}
+
+impl X87Instruction {
+    /// Look up the instruction for the ESC opcode byte and the fields of its ModRM byte.
+    /// Encodings the 8087 does not define yield `None`.
+    pub(crate) fn decode(opcode: u8, mod_field: u8, reg: u8, rm: u8) -> Option<Self> {
+        let (mnemonic, operands) = if mod_field == 0b11 {
+            Self::decode_register(opcode & 0b111, reg, rm)?
+        } else {
+            Self::decode_memory(opcode & 0b111, reg)?
+        };
+        Some(X87Instruction { mnemonic, operands })
+    }
+
+    /// Look up the forms that take a memory operand
+    fn decode_memory(escape: u8, reg: u8) -> Option<(Mnemonic, X87Operands)> {
+        let memory = |mnemonic, size| Some((mnemonic, X87Operands::Memory(size)));
+        match (escape, reg) {
+            (0b000, _) => memory(Self::float(reg), Some(OperandSize::Dword)),
+            (0b100, _) => memory(Self::float(reg), Some(OperandSize::Qword)),
+            (0b010, _) => memory(Self::integer(reg), Some(OperandSize::Dword)),
+            (0b110, _) => memory(Self::integer(reg), Some(OperandSize::Word)),
+            (0b001, 0b000) => memory(Mnemonic::Fld, Some(OperandSize::Dword)),
+            (0b001, 0b010) => memory(Mnemonic::Fst, Some(OperandSize::Dword)),
+            (0b001, 0b011) => memory(Mnemonic::Fstp, Some(OperandSize::Dword)),
+            (0b001, 0b100) => memory(Mnemonic::Fldenv, None),
+            (0b001, 0b101) => memory(Mnemonic::Fldcw, Some(OperandSize::Word)),
+            (0b001, 0b110) => memory(Mnemonic::Fnstenv, None),
+            (0b001, 0b111) => memory(Mnemonic::Fnstcw, Some(OperandSize::Word)),
+            (0b011, 0b000) => memory(Mnemonic::Fild, Some(OperandSize::Dword)),
+            (0b011, 0b010) => memory(Mnemonic::Fist, Some(OperandSize::Dword)),
+            (0b011, 0b011) => memory(Mnemonic::Fistp, Some(OperandSize::Dword)),
+            (0b011, 0b101) => memory(Mnemonic::Fld, Some(OperandSize::Tword)),
+            (0b011, 0b111) => memory(Mnemonic::Fstp, Some(OperandSize::Tword)),
+            (0b101, 0b000) => memory(Mnemonic::Fld, Some(OperandSize::Qword)),
+            (0b101, 0b010) => memory(Mnemonic::Fst, Some(OperandSize::Qword)),
+            (0b101, 0b011) => memory(Mnemonic::Fstp, Some(OperandSize::Qword)),
+            (0b101, 0b100) => memory(Mnemonic::Frstor, None),
+            (0b101, 0b110) => memory(Mnemonic::Fnsave, None),
+            (0b101, 0b111) => memory(Mnemonic::Fnstsw, Some(OperandSize::Word)),
+            (0b111, 0b000) => memory(Mnemonic::Fild, Some(OperandSize::Word)),
+            (0b111, 0b010) => memory(Mnemonic::Fist, Some(OperandSize::Word)),
+            (0b111, 0b011) => memory(Mnemonic::Fistp, Some(OperandSize::Word)),
+            (0b111, 0b100) => memory(Mnemonic::Fbld, Some(OperandSize::Tword)),
+            (0b111, 0b101) => memory(Mnemonic::Fild, Some(OperandSize::Qword)),
+            (0b111, 0b110) => memory(Mnemonic::Fbstp, Some(OperandSize::Tword)),
+            (0b111, 0b111) => memory(Mnemonic::Fistp, Some(OperandSize::Qword)),
+            _ => None,
+        }
+    }
+
+    /// Look up the forms that work on the register stack
+    fn decode_register(escape: u8, reg: u8, rm: u8) -> Option<(Mnemonic, X87Operands)> {
+        let implied = |mnemonic| Some((mnemonic, X87Operands::None));
+        match (escape, reg, rm) {
+            (0b000, _, _) => Some((Self::float(reg), X87Operands::StackTopWithRegister)),
+            // With the stack top as source the reversed subtraction and division swap places
+            (0b100, 0b000 | 0b001 | 0b100..=0b111, _) => Some((
+                Self::float(reg ^ ((reg >> 2) & 0b001)),
+                X87Operands::RegisterWithStackTop,
+            )),
+            (0b001, 0b000, _) => Some((Mnemonic::Fld, X87Operands::Register)),
+            (0b001, 0b001, _) => Some((Mnemonic::Fxch, X87Operands::Register)),
+            (0b001, 0b010, 0b000) => implied(Mnemonic::Fnop),
+            (0b001, 0b100, 0b000) => implied(Mnemonic::Fchs),
+            (0b001, 0b100, 0b001) => implied(Mnemonic::Fabs),
+            (0b001, 0b100, 0b100) => implied(Mnemonic::Ftst),
+            (0b001, 0b100, 0b101) => implied(Mnemonic::Fxam),
+            (0b001, 0b101, 0b000) => implied(Mnemonic::Fld1),
+            (0b001, 0b101, 0b001) => implied(Mnemonic::Fldl2t),
+            (0b001, 0b101, 0b010) => implied(Mnemonic::Fldl2e),
+            (0b001, 0b101, 0b011) => implied(Mnemonic::Fldpi),
+            (0b001, 0b101, 0b100) => implied(Mnemonic::Fldlg2),
+            (0b001, 0b101, 0b101) => implied(Mnemonic::Fldln2),
+            (0b001, 0b101, 0b110) => implied(Mnemonic::Fldz),
+            (0b001, 0b110, 0b000) => implied(Mnemonic::F2xm1),
+            (0b001, 0b110, 0b001) => implied(Mnemonic::Fyl2x),
+            (0b001, 0b110, 0b010) => implied(Mnemonic::Fptan),
+            (0b001, 0b110, 0b011) => implied(Mnemonic::Fpatan),
+            (0b001, 0b110, 0b100) => implied(Mnemonic::Fxtract),
+            (0b001, 0b110, 0b110) => implied(Mnemonic::Fdecstp),
+            (0b001, 0b110, 0b111) => implied(Mnemonic::Fincstp),
+            (0b001, 0b111, 0b000) => implied(Mnemonic::Fprem),
+            (0b001, 0b111, 0b001) => implied(Mnemonic::Fyl2xp1),
+            (0b001, 0b111, 0b010) => implied(Mnemonic::Fsqrt),
+            (0b001, 0b111, 0b100) => implied(Mnemonic::Frndint),
+            (0b001, 0b111, 0b101) => implied(Mnemonic::Fscale),
+            (0b011, 0b100, 0b000) => implied(Mnemonic::Fneni),
+            (0b011, 0b100, 0b001) => implied(Mnemonic::Fndisi),
+            (0b011, 0b100, 0b010) => implied(Mnemonic::Fnclex),
+            (0b011, 0b100, 0b011) => implied(Mnemonic::Fninit),
+            (0b101, 0b000, _) => Some((Mnemonic::Ffree, X87Operands::Register)),
+            (0b101, 0b010, _) => Some((Mnemonic::Fst, X87Operands::Register)),
+            (0b101, 0b011, _) => Some((Mnemonic::Fstp, X87Operands::Register)),
+            (0b110, 0b011, 0b001) => implied(Mnemonic::Fcompp),
+            (0b110, 0b000 | 0b001 | 0b100..=0b111, _) => Some((
+                Self::popping(reg ^ ((reg >> 2) & 0b001)),
+                X87Operands::RegisterWithStackTop,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Look up the real arithmetic operation selected by the reg field
+    fn float(reg: u8) -> Mnemonic {
+        [
+            Mnemonic::Fadd,
+            Mnemonic::Fmul,
+            Mnemonic::Fcom,
+            Mnemonic::Fcomp,
+            Mnemonic::Fsub,
+            Mnemonic::Fsubr,
+            Mnemonic::Fdiv,
+            Mnemonic::Fdivr,
+        ][reg as usize & 0b111]
+    }
+
+    /// Look up the integer arithmetic operation selected by the reg field
+    fn integer(reg: u8) -> Mnemonic {
+        [
+            Mnemonic::Fiadd,
+            Mnemonic::Fimul,
+            Mnemonic::Ficom,
+            Mnemonic::Ficomp,
+            Mnemonic::Fisub,
+            Mnemonic::Fisubr,
+            Mnemonic::Fidiv,
+            Mnemonic::Fidivr,
+        ][reg as usize & 0b111]
+    }
+
+    /// Look up the arithmetic operation that pops the stack, selected by the reg field
+    fn popping(reg: u8) -> Mnemonic {
+        [
+            Mnemonic::Faddp,
+            Mnemonic::Fmulp,
+            Mnemonic::Fcomp,
+            Mnemonic::Fcompp,
+            Mnemonic::Fsubp,
+            Mnemonic::Fsubrp,
+            Mnemonic::Fdivp,
+            Mnemonic::Fdivrp,
+        ][reg as usize & 0b111]
+    }
+
+    /// Get the form of a control instruction that waits for the 8087 first, which assemblers
+    /// encode as `wait` in front of the form that does not
+    pub(crate) fn waiting(&self) -> Option<Self> {
+        let mnemonic = match self.mnemonic {
+            Mnemonic::Fninit => Mnemonic::Finit,
+            Mnemonic::Fnclex => Mnemonic::Fclex,
+            Mnemonic::Fneni => Mnemonic::Feni,
+            Mnemonic::Fndisi => Mnemonic::Fdisi,
+            Mnemonic::Fnstsw => Mnemonic::Fstsw,
+            Mnemonic::Fnstcw => Mnemonic::Fstcw,
+            Mnemonic::Fnstenv => Mnemonic::Fstenv,
+            Mnemonic::Fnsave => Mnemonic::Fsave,
+            _ => return None,
+        };
+        Some(X87Instruction { mnemonic, ..*self })
+    }
+
+    /// Get the floating point operation
+    pub(crate) fn mnemonic(&self) -> Mnemonic {
+        self.mnemonic
+    }
+
+    /// Build the operands from the one the mod and R/M fields select for memory forms
+    pub(crate) fn operands(&self, rm: Operand, rm_field: u8) -> Operands {
+        let top = Operand::Register(Register::St(0));
+        let register = Operand::Register(Register::St(rm_field & 0b111));
+        match self.operands {
+            X87Operands::None => Operands::default(),
+            X87Operands::Memory(Some(size)) => Operands::from([rm.with_size(size)]),
+            X87Operands::Memory(None) => Operands::from([rm]),
+            X87Operands::Register => Operands::from([register]),
+            X87Operands::StackTopWithRegister => Operands::from([top, register]),
+            X87Operands::RegisterWithStackTop => Operands::from([register, top]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_memory_sizes() {
+        let cases = [
+            (0xD8, 0b000, Mnemonic::Fadd, Some(OperandSize::Dword)),
+            (0xDC, 0b000, Mnemonic::Fadd, Some(OperandSize::Qword)),
+            (0xDA, 0b000, Mnemonic::Fiadd, Some(OperandSize::Dword)),
+            (0xDE, 0b000, Mnemonic::Fiadd, Some(OperandSize::Word)),
+            (0xDB, 0b101, Mnemonic::Fld, Some(OperandSize::Tword)),
+            (0xDF, 0b110, Mnemonic::Fbstp, Some(OperandSize::Tword)),
+            (0xDD, 0b110, Mnemonic::Fnsave, None),
+        ];
+        for (opcode, reg, mnemonic, size) in cases {
+            assert_eq!(
+                X87Instruction::decode(opcode, 0b00, reg, 0b111),
+                Some(X87Instruction {
+                    mnemonic,
+                    operands: X87Operands::Memory(size),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_reversed_operations() {
+        let cases = [
+            (0xD8, 0b100, Mnemonic::Fsub),
+            (0xD8, 0b101, Mnemonic::Fsubr),
+            (0xDC, 0b100, Mnemonic::Fsubr),
+            (0xDC, 0b101, Mnemonic::Fsub),
+            (0xDC, 0b110, Mnemonic::Fdivr),
+            (0xDC, 0b111, Mnemonic::Fdiv),
+            (0xDE, 0b100, Mnemonic::Fsubrp),
+            (0xDE, 0b111, Mnemonic::Fdivp),
+        ];
+        for (opcode, reg, mnemonic) in cases {
+            assert_eq!(
+                X87Instruction::decode(opcode, 0b11, reg, 0b001).map(|x87| x87.mnemonic),
+                Some(mnemonic)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_undefined_encodings() {
+        assert_eq!(X87Instruction::decode(0xD9, 0b00, 0b001, 0b000), None);
+        assert_eq!(X87Instruction::decode(0xDA, 0b11, 0b000, 0b000), None);
+        assert_eq!(X87Instruction::decode(0xD9, 0b11, 0b111, 0b011), None);
+        assert_eq!(X87Instruction::decode(0xDE, 0b11, 0b011, 0b000), None);
+    }
+
+    #[test]
+    fn test_waiting_forms() {
+        let fninit = X87Instruction::decode(0xDB, 0b11, 0b100, 0b011).unwrap();
+        assert_eq!(fninit.waiting().unwrap().mnemonic, Mnemonic::Finit);
+        let fnstsw = X87Instruction::decode(0xDD, 0b00, 0b111, 0b111).unwrap();
+        assert_eq!(
+            fnstsw.waiting(),
+            Some(X87Instruction {
+                mnemonic: Mnemonic::Fstsw,
+                operands: X87Operands::Memory(Some(OperandSize::Word)),
+            })
+        );
+        let fld = X87Instruction::decode(0xD9, 0b11, 0b000, 0b001).unwrap();
+        assert_eq!(fld.waiting(), None);
+    }
+}
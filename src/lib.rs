@@ -1,3 +1,19 @@
+//! Decoding of 8086 machine code into structured instructions and NASM-style listings.
+//!
+//! A [`Decoder`] turns the bytes of one instruction into an [`Instruction`], whose
+//! [`Mnemonic`] and [`Operand`]s can be inspected and which formats itself as assembly.
+//! [`disassemble`] and [`disassemble_with`] turn a whole file into a listing.
+//!
+//! ```
+//! use inst_decoding_8086::{Decoder, Cpu, JumpTarget};
+//!
+//! let decoder = Decoder::new(Cpu::Intel8086);
+//! let instruction = decoder.decode(&[0x75, 0xFC], 0x100).unwrap();
+//! assert_eq!(instruction.format(JumpTarget::Absolute), "jnz 254");
+//! ```
+
+#![warn(missing_docs)]
+
 mod instruction_set;
 pub mod utils;
 
@@ -5,12 +21,40 @@ use std::fs;
 use std::io::Result;
 use std::path::Path;
 
-use instruction_set::X86InstructionError;
+pub use crate::instruction_set::{
+    Cpu, DecodeError, DecodeIter, Decoder, EffectiveAddress, Instruction, JumpTarget, Mnemonic,
+    Operand, OperandSize, Operands, Prefixes, Register, Repeat,
+};
 
-use crate::instruction_set::X86Instruction;
+/// Decodes the 8086 instructions in `bytes` lazily, the first of them at address `origin`.
+///
+/// Nothing is allocated per instruction, so large images can be scanned a few instructions at a
+/// time. Use [`Decoder::decode_iter`] to decode the instruction set of another processor.
+///
+/// # Examples
+/// ```
+/// use inst_decoding_8086::decode_iter;
+///
+/// let listing: Vec<String> = decode_iter(&[0x89, 0xD9, 0x75, 0xFC], 0x100)
+///     .map(|instruction| instruction.unwrap().to_string())
+///     .collect();
+/// assert_eq!(listing, ["mov cx, bx", "jnz $+2-4"]);
+///
+/// // Decoding ends at the first bytes that are no instruction
+/// let mut instructions = decode_iter(&[0x90, 0xFF, 0xFF], 0);
+/// assert!(instructions.next().unwrap().is_ok());
+/// assert!(instructions.next().unwrap().is_err());
+/// assert!(instructions.next().is_none());
+/// ```
+pub fn decode_iter(bytes: &[u8], origin: u16) -> DecodeIter<'_> {
+    Decoder::default().decode_iter(bytes, origin)
+}
 
 /// Dissassembles a file into a string.
 ///
+/// Instructions are decoded one after another, each consuming as many bytes as its encoding
+/// requires.
+///
 /// # Examples
 /// ```
 /// use inst_decoding_8086::disassemble;
@@ -31,18 +75,47 @@ use crate::instruction_set::X86Instruction;
 /// assert_eq!(listing, "bits 16\nmov cx, bx");
 /// ```
 pub fn disassemble(file: &Path) -> Result<String> {
+    disassemble_with(file, JumpTarget::default(), Cpu::default())
+}
+
+/// Dissassembles a file into a string, printing relative jump targets as `jump_target` asks for
+/// and accepting the instruction set of `cpu`.
+///
+/// # Examples
+/// ```
+/// use inst_decoding_8086::{disassemble_with, Cpu, JumpTarget};
+/// use std::io::Write;
+/// use tempfile::NamedTempFile;
+///
+/// // A `jnz` that jumps back onto the `mov` in front of it
+/// let mut temp_file = NamedTempFile::new().unwrap();
+/// temp_file.write_all(&[0x89, 0xD9, 0x75, 0xFC]).unwrap();
+/// temp_file.flush().unwrap();
+///
+/// let relative =
+///     disassemble_with(temp_file.path(), JumpTarget::Relative, Cpu::Intel8086).unwrap();
+/// assert_eq!(relative, "bits 16\nmov cx, bx\njnz $+2-4");
+/// let absolute =
+///     disassemble_with(temp_file.path(), JumpTarget::Absolute, Cpu::Intel8086).unwrap();
+/// assert_eq!(absolute, "bits 16\nmov cx, bx\njnz 0");
+///
+/// // `pusha` only exists from the 80186 on
+/// let mut temp_file = NamedTempFile::new().unwrap();
+/// temp_file.write_all(&[0x60]).unwrap();
+/// temp_file.flush().unwrap();
+///
+/// let listing =
+///     disassemble_with(temp_file.path(), JumpTarget::Relative, Cpu::Intel80186).unwrap();
+/// assert_eq!(listing, "bits 16\npusha");
+/// assert!(disassemble_with(temp_file.path(), JumpTarget::Relative, Cpu::Intel8086).is_err());
+/// ```
+pub fn disassemble_with(file: &Path, jump_target: JumpTarget, cpu: Cpu) -> Result<String> {
     let mut listing = String::from("bits 16\n");
 
     let buffer: Vec<u8> = fs::read(file)?;
-    for chunk in buffer.chunks(2) {
-        let instruction = X86Instruction::new([chunk[0], chunk[1]]);
-        match instruction.format_instruction() {
-            Ok(formatted_instruction) => {
-                listing.push_str(&formatted_instruction);
-                listing.push('\n');
-            }
-            _ => return Err(X86InstructionError::InvalidInstruction.into()),
-        }
+    for instruction in Decoder::new(cpu).decode_iter(&buffer, 0) {
+        listing.push_str(&instruction?.format(jump_target));
+        listing.push('\n');
     }
     Ok(listing.trim().into())
 }
@@ -56,11 +129,16 @@ mod tests {
     use tempfile::NamedTempFile;
 
     use super::*;
+    use crate::instruction_set::errors::DecodeError;
     use std::fs;
     const SINGLE: &str = "tests/test_data/listing_0037_single_register_mov";
     const MANY: &str = "tests/test_data/listing_0038_many_register_mov";
+    const MORE_MOVS: &str = "tests/test_data/listing_0039_more_movs";
+    const CHALLENGE_MOVS: &str = "tests/test_data/listing_0040_challenge_movs";
     const SINGLE_ASM: &str = "tests/test_data/listing_0037_single_register_mov.asm";
     const MANY_ASM: &str = "tests/test_data/listing_0038_many_register_mov.asm";
+    const MORE_MOVS_ASM: &str = "tests/test_data/listing_0039_more_movs.asm";
+    const CHALLENGE_MOVS_ASM: &str = "tests/test_data/listing_0040_challenge_movs.asm";
 
     #[test]
     fn test_single_register() {
@@ -82,6 +160,45 @@ mod tests {
         assert_eq!(normalized_fun_name, normalized_stripped_asm_content);
     }
 
+    #[test]
+    fn test_more_movs() {
+        let asm_path = Path::new(MORE_MOVS_ASM);
+        let asm_content = fs::read_to_string(asm_path).unwrap();
+        let normalized_stripped_asm_content = preprocess_listing(&asm_content);
+        let path = Path::new(MORE_MOVS);
+        let normalized_fun_name = disassemble(path).unwrap();
+        assert_eq!(normalized_fun_name, normalized_stripped_asm_content);
+    }
+
+    #[test]
+    fn test_challenge_movs() {
+        let asm_path = Path::new(CHALLENGE_MOVS_ASM);
+        let asm_content = fs::read_to_string(asm_path).unwrap();
+        let normalized_stripped_asm_content = preprocess_listing(&asm_content);
+        let path = Path::new(CHALLENGE_MOVS);
+        let normalized_fun_name = disassemble(path).unwrap();
+        assert_eq!(normalized_fun_name, normalized_stripped_asm_content);
+    }
+
+    #[test]
+    fn test_jump_targets_track_instruction_addresses() {
+        // A loop back onto the add, followed by a jcxz to the end of the file
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file
+            .write_all(&[0xB9, 0x03, 0x00, 0x01, 0xC8, 0xE2, 0xFC, 0xE3, 0x00])
+            .unwrap();
+        temp_file.flush().unwrap();
+
+        let relative = disassemble(temp_file.path()).unwrap();
+        assert_eq!(
+            relative,
+            "bits 16\nmov cx, 3\nadd ax, cx\nloop $+2-4\njcxz $+2+0"
+        );
+        let absolute =
+            disassemble_with(temp_file.path(), JumpTarget::Absolute, Cpu::Intel8086).unwrap();
+        assert_eq!(absolute, "bits 16\nmov cx, 3\nadd ax, cx\nloop 3\njcxz 9");
+    }
+
     #[test]
     fn test_disassemble_file_not_found() {
         let non_existent_path = Path::new("some_non_existent_file");
@@ -104,7 +221,24 @@ mod tests {
         let inner_err = result.get_ref().unwrap();
         assert!(matches!(
             inner_err.downcast_ref(),
-            Some(&X86InstructionError::InvalidInstruction)
+            Some(&DecodeError::InvalidInstruction)
+        ));
+    }
+
+    #[test]
+    fn test_incomplete_instruction() {
+        // An odd-length file ends in the middle of the second instruction
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&[0x89, 0xD9, 0x88]).unwrap();
+        temp_file.flush().unwrap();
+
+        let result = disassemble(temp_file.path()).unwrap_err();
+
+        assert!(matches!(result.kind(), ErrorKind::Other));
+        let inner_err = result.get_ref().unwrap();
+        assert!(matches!(
+            inner_err.downcast_ref(),
+            Some(&DecodeError::IncompleteInstruction)
         ));
     }
 }
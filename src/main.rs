@@ -3,17 +3,23 @@ use std::fs::File;
 use std::io::{Result, Write};
 use std::path::Path;
 
-use inst_decoding_8086::disassemble;
+use inst_decoding_8086::{disassemble_with, Cpu, JumpTarget};
+
+/// Flag asking for jump targets as absolute addresses instead of NASM-style relative offsets
+const ABSOLUTE_FLAG: &str = "--absolute";
+/// Flag selecting the processor whose instruction set is decoded, such as `--cpu=186`
+const CPU_FLAG: &str = "--cpu=";
 
 fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
+    let (args, jump_target) = get_jump_target(env::args().collect());
+    let (args, cpu) = get_cpu(args)?;
     let input_filename = args
         .get(1)
         .ok_or("Input file name is required")
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
     let output_filename = get_output_filename(&args, input_filename);
     let input_path = Path::new(&input_filename);
-    let listing = disassemble(input_path)?;
+    let listing = disassemble_with(input_path, jump_target, cpu)?;
     println!("{}", listing);
     let mut file = File::create(output_filename)?;
     file.write_all(listing.as_bytes())?;
@@ -30,6 +36,40 @@ fn get_output_filename(args: &[String], default_input: &str) -> String {
     }
 }
 
+fn get_jump_target(args: Vec<String>) -> (Vec<String>, JumpTarget) {
+    if args.iter().any(|arg| arg == ABSOLUTE_FLAG) {
+        let args = args
+            .into_iter()
+            .filter(|arg| arg != ABSOLUTE_FLAG)
+            .collect();
+        (args, JumpTarget::Absolute)
+    } else {
+        (args, JumpTarget::Relative)
+    }
+}
+
+fn get_cpu(args: Vec<String>) -> Result<(Vec<String>, Cpu)> {
+    let mut cpu = Cpu::default();
+    let mut positional = Vec::new();
+    for arg in args {
+        match arg.strip_prefix(CPU_FLAG) {
+            Some("8086" | "8088") => cpu = Cpu::Intel8086,
+            Some("silicon") => cpu = Cpu::Intel8086Silicon,
+            Some("186" | "188" | "80186" | "80188") => cpu = Cpu::Intel80186,
+            Some("286" | "80286") => cpu = Cpu::Intel80286,
+            Some("v20" | "v30") => cpu = Cpu::NecV20,
+            Some(name) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("Unknown CPU {}", name),
+                ))
+            }
+            None => positional.push(arg),
+        }
+    }
+    Ok((positional, cpu))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,4 +93,58 @@ mod tests {
         let expected = "output";
         assert_eq!(get_output_filename(&args, default_input), expected);
     }
+
+    #[test]
+    fn test_get_jump_target() {
+        let args = vec!["decode".to_string(), "input".to_string()];
+        let (positional, jump_target) = get_jump_target(args.clone());
+        assert_eq!(positional, args);
+        assert_eq!(jump_target, JumpTarget::Relative);
+    }
+
+    #[test]
+    fn test_get_jump_target_with_absolute_flag() {
+        let args = vec![
+            "decode".to_string(),
+            "--absolute".to_string(),
+            "input".to_string(),
+        ];
+        let (positional, jump_target) = get_jump_target(args);
+        assert_eq!(positional, vec!["decode".to_string(), "input".to_string()]);
+        assert_eq!(jump_target, JumpTarget::Absolute);
+    }
+
+    #[test]
+    fn test_get_cpu() {
+        let args = vec!["decode".to_string(), "input".to_string()];
+        let (positional, cpu) = get_cpu(args.clone()).unwrap();
+        assert_eq!(positional, args);
+        assert_eq!(cpu, Cpu::Intel8086);
+    }
+
+    #[test]
+    fn test_get_cpu_with_cpu_flag() {
+        let args = vec![
+            "decode".to_string(),
+            "input".to_string(),
+            "--cpu=186".to_string(),
+        ];
+        let (positional, cpu) = get_cpu(args).unwrap();
+        assert_eq!(positional, vec!["decode".to_string(), "input".to_string()]);
+        assert_eq!(cpu, Cpu::Intel80186);
+        let args = vec!["decode".to_string(), "--cpu=80286".to_string()];
+        assert_eq!(get_cpu(args).unwrap().1, Cpu::Intel80286);
+        let args = vec!["decode".to_string(), "--cpu=silicon".to_string()];
+        assert_eq!(get_cpu(args).unwrap().1, Cpu::Intel8086Silicon);
+        let args = vec!["decode".to_string(), "--cpu=v30".to_string()];
+        assert_eq!(get_cpu(args).unwrap().1, Cpu::NecV20);
+    }
+
+    #[test]
+    fn test_get_cpu_with_unknown_cpu() {
+        let args = vec!["decode".to_string(), "--cpu=z80".to_string()];
+        let error = get_cpu(args).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "Unknown CPU z80");
+    }
 }
@@ -1,3 +1,5 @@
+//! Helpers for comparing assembly listings
+
 /// Preprocesses a listing by removing comments and extra newlines.
 ///
 /// # Examples
@@ -31,6 +31,52 @@ fn test_command_line_tool() {
     assert_eq!(expected_output, real_output);
 }
 
+#[test]
+fn test_command_line_tool_with_absolute_jump_targets() {
+    let mut input_file = NamedTempFile::new().unwrap();
+    let output_file = NamedTempFile::new().unwrap();
+
+    input_file.write_all(&[0x89, 0xD9, 0x75, 0xFC]).unwrap();
+
+    let input_path = input_file.path().to_str().unwrap();
+    let output_path = output_file.path().to_str().unwrap();
+
+    let mut cmd = Command::new("target/debug/decode");
+    cmd.arg("--absolute").arg(input_path).arg(output_path);
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let expected_output = "bits 16\nmov cx, bx\njnz 0";
+    let real_output = fs::read_to_string(output_path).unwrap();
+    assert_eq!(expected_output, real_output);
+}
+
+#[test]
+fn test_command_line_tool_with_cpu() {
+    let mut input_file = NamedTempFile::new().unwrap();
+    let output_file = NamedTempFile::new().unwrap();
+
+    input_file
+        .write_all(&[0x60, 0xC8, 0x08, 0x00, 0x00])
+        .unwrap();
+
+    let input_path = input_file.path().to_str().unwrap();
+    let output_path = output_file.path().to_str().unwrap();
+
+    let mut cmd = Command::new("target/debug/decode");
+    cmd.arg("--cpu=186").arg(input_path).arg(output_path);
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let expected_output = "bits 16\npusha\nenter 8, 0";
+    let real_output = fs::read_to_string(output_path).unwrap();
+    assert_eq!(expected_output, real_output);
+
+    let mut cmd = Command::new("target/debug/decode");
+    cmd.arg(input_path).arg(output_path);
+    assert!(!cmd.output().unwrap().status.success());
+}
+
 #[test]
 fn test_command_line_tool_with_missing_filename() {
     let mut cmd = Command::new("target/debug/decode");
@@ -91,6 +137,8 @@ fn test_functional_coverage() {
     for filename in [
         "listing_0037_single_register_mov",
         "listing_0038_many_register_mov",
+        "listing_0039_more_movs",
+        "listing_0040_challenge_movs",
     ] {
         let input_path = format!("tests/test_data/{}", filename);
         let named_tempfile = Builder::new()